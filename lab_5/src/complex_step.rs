@@ -0,0 +1,172 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::dual::Scalar;
+
+// Complex numbers for the complex step, also used by the polynomial root
+// finders of lab_6. Every function keeps the imaginary part free of
+// cancellation, so that a tiny im is carried through accurately.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(r: f64, theta: f64) -> Complex {
+        Complex::new(r * theta.cos(), r * theta.sin())
+    }
+
+    pub fn abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Complex {
+        Complex::new(re, 0.0)
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im < 0.0 {
+            write!(f, "{} - {}i", self.re, -self.im)
+        } else {
+            write!(f, "{} + {}i", self.re, self.im)
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, other: Complex) -> Complex {
+        let d = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / d,
+            (self.im * other.re - self.re * other.im) / d,
+        )
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl Scalar for Complex {
+    fn variable(x: f64) -> Complex {
+        Complex::from(x)
+    }
+    fn value(&self) -> f64 {
+        self.re
+    }
+    fn powi(self, n: i32) -> Complex {
+        // Repeated squaring, so no logarithm of a number near the real axis is needed
+        let mut result = Complex::from(1.0);
+        let mut base = self;
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k % 2 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            k /= 2;
+        }
+        if n < 0 {
+            Complex::from(1.0) / result
+        } else {
+            result
+        }
+    }
+    fn sin(self) -> Complex {
+        Complex::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+    fn cos(self) -> Complex {
+        Complex::new(
+            self.re.cos() * self.im.cosh(),
+            -self.re.sin() * self.im.sinh(),
+        )
+    }
+    fn exp(self) -> Complex {
+        let r = self.re.exp();
+        Complex::new(r * self.im.cos(), r * self.im.sin())
+    }
+    fn ln(self) -> Complex {
+        Complex::new(self.abs().ln(), self.arg())
+    }
+    fn sqrt(self) -> Complex {
+        // The larger part is computed directly and the smaller one from
+        // im = 2 re im / (2 re), which avoids r - |re| for a small im
+        let r = self.abs();
+        if self.re >= 0.0 {
+            let re = ((r + self.re) / 2.0).sqrt();
+            if re == 0.0 {
+                return Complex::from(0.0);
+            }
+            Complex::new(re, self.im / (2.0 * re))
+        } else {
+            let im = ((r - self.re) / 2.0).sqrt().copysign(self.im);
+            Complex::new(self.im / (2.0 * im), im)
+        }
+    }
+    fn asinh(self) -> Complex {
+        // asinh is odd; using it for re < 0 avoids cancellation in z + sqrt(z^2 + 1)
+        if self.re < 0.0 {
+            return -(-self).asinh();
+        }
+        (self + (self * self + Complex::from(1.0)).sqrt()).ln()
+    }
+    fn tanh(self) -> Complex {
+        let (a, b) = (2.0 * self.re, 2.0 * self.im);
+        let d = a.cosh() + b.cos();
+        Complex::new(a.sinh() / d, b.sin() / d)
+    }
+}
+
+// f'(x) ~ Im f(x + ih) / h. There is no subtraction, so h can be made as small
+// as the exponent range allows and the only error left is the O(h^2) truncation.
+pub fn complex_step_derivative(f: impl Fn(Complex) -> Complex, x: f64, h: f64) -> f64 {
+    f(Complex::new(x, h)).im / h
+}
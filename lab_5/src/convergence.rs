@@ -0,0 +1,259 @@
+use std::fmt;
+
+use plotly::common::{Mode, Title};
+use plotly::layout::{Axis, AxisType, Layout};
+use plotly::{Plot, Scatter};
+
+// Local slopes within this fraction of the median slope count as asymptotic
+const ASYMPTOTIC_TOLERANCE: f64 = 0.1;
+
+// What the study sweeps. The error falls as h decreases or as n increases, so
+// the coarse end, where the truncation error dominates, is at large h or small n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameter {
+    // Step size h, error ~ C h^p
+    Step,
+    // Number of points or panels n, error ~ C n^-p
+    Count,
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parameter::Step => f.pad("h"),
+            Parameter::Count => f.pad("n"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrderFit {
+    pub order: f64,
+    // Parameter range of the points used for the fit
+    pub from: f64,
+    pub to: f64,
+    pub points: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodResult {
+    pub name: String,
+    pub values: Vec<f64>,
+    pub errors: Vec<f64>,
+    // Observed order of convergence, None without a usable asymptotic region
+    pub order: Option<OrderFit>,
+    // Parameter with the smallest error and that error
+    pub optimum: (f64, f64),
+}
+
+pub type Approximation<'a> = Box<dyn Fn(f64) -> f64 + 'a>;
+
+pub struct ConvergenceStudy<'a> {
+    pub title: String,
+    pub parameter: Parameter,
+    pub sweep: Vec<f64>,
+    pub reference: f64,
+    methods: Vec<(String, Approximation<'a>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StudyTable {
+    pub title: String,
+    pub parameter: Parameter,
+    pub sweep: Vec<f64>,
+    pub reference: f64,
+    pub results: Vec<MethodResult>,
+}
+
+impl<'a> ConvergenceStudy<'a> {
+    pub fn new(title: &str, parameter: Parameter, sweep: Vec<f64>, reference: f64) -> Self {
+        ConvergenceStudy {
+            title: title.to_string(),
+            parameter,
+            sweep,
+            reference,
+            methods: Vec::new(),
+        }
+    }
+
+    // Adds a method computing the approximation for one value of the parameter
+    pub fn method(mut self, name: &str, approximation: impl Fn(f64) -> f64 + 'a) -> Self {
+        self.methods
+            .push((name.to_string(), Box::new(approximation)));
+        self
+    }
+
+    pub fn run(&self) -> StudyTable {
+        let results = self
+            .methods
+            .iter()
+            .map(|(name, approximation)| {
+                let values = self
+                    .sweep
+                    .iter()
+                    .map(|p| approximation(*p))
+                    .collect::<Vec<f64>>();
+                let errors = values
+                    .iter()
+                    .map(|v| (v - self.reference).abs())
+                    .collect::<Vec<f64>>();
+                let best = (0..errors.len())
+                    .filter(|i| errors[*i].is_finite())
+                    .min_by(|a, b| errors[*a].total_cmp(&errors[*b]));
+                let optimum = best.map_or((f64::NAN, f64::NAN), |i| (self.sweep[i], errors[i]));
+                let order = best.and_then(|i| fit_order(&self.sweep, &errors, self.parameter, i));
+                MethodResult {
+                    name: name.clone(),
+                    values,
+                    errors,
+                    order,
+                    optimum,
+                }
+            })
+            .collect();
+        StudyTable {
+            title: self.title.clone(),
+            parameter: self.parameter,
+            sweep: self.sweep.clone(),
+            reference: self.reference,
+            results,
+        }
+    }
+}
+
+// Least squares line through log(error) against log(parameter) on the coarse
+// side of the optimum. Pre-asymptotic points at the coarse end and points
+// spoiled by round-off near the optimum are left out by keeping the longest
+// run of consecutive local slopes close to their median.
+fn fit_order(
+    sweep: &[f64],
+    errors: &[f64],
+    parameter: Parameter,
+    optimum: usize,
+) -> Option<OrderFit> {
+    let finer = |a: f64, b: f64| match parameter {
+        Parameter::Step => a < b,
+        Parameter::Count => a > b,
+    };
+    let mut coarse = (0..sweep.len())
+        .filter(|i| !finer(sweep[*i], sweep[optimum]) && sweep[*i] > 0.0)
+        .filter(|i| errors[*i] > 0.0 && errors[*i].is_finite())
+        .collect::<Vec<usize>>();
+    coarse.sort_by(|a, b| match parameter {
+        Parameter::Step => sweep[*b].total_cmp(&sweep[*a]),
+        Parameter::Count => sweep[*a].total_cmp(&sweep[*b]),
+    });
+    coarse.dedup_by(|a, b| sweep[*a] == sweep[*b]);
+    if coarse.len() < 2 {
+        return None;
+    }
+    let points = coarse
+        .iter()
+        .map(|i| (sweep[*i].ln(), errors[*i].ln()))
+        .collect::<Vec<(f64, f64)>>();
+    let slopes = points
+        .windows(2)
+        .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+        .collect::<Vec<f64>>();
+    let mut sorted = slopes.clone();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    let close = |s: f64| (s - median).abs() <= ASYMPTOTIC_TOLERANCE * median.abs().max(1.0);
+
+    // Longest run of close slopes; run (start, length) in slopes
+    let mut best = (0, 0);
+    let mut start = 0;
+    for (k, s) in slopes.iter().enumerate() {
+        if !close(*s) {
+            start = k + 1;
+        } else if k + 1 - start > best.1 {
+            best = (start, k + 1 - start);
+        }
+    }
+    if best.1 == 0 {
+        return None;
+    }
+    let run = &points[best.0..=best.0 + best.1];
+    let n = run.len() as f64;
+    let mean_x = run.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = run.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxy = run
+        .iter()
+        .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+        .sum::<f64>();
+    let sxx = run.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+    let slope = sxy / sxx;
+    let ends = [sweep[coarse[best.0]], sweep[coarse[best.0 + best.1]]];
+    Some(OrderFit {
+        order: match parameter {
+            Parameter::Step => slope,
+            Parameter::Count => -slope,
+        },
+        from: ends[0].min(ends[1]),
+        to: ends[0].max(ends[1]),
+        points: run.len(),
+    })
+}
+
+impl StudyTable {
+    pub fn print(&self) {
+        println!("{} (reference value {})", self.title, self.reference);
+        let header = self
+            .results
+            .iter()
+            .map(|r| format!("{:>24}", r.name))
+            .collect::<Vec<String>>();
+        println!("{:>12} {}", self.parameter, header.join(" "));
+        for (i, p) in self.sweep.iter().enumerate() {
+            let errors = self
+                .results
+                .iter()
+                .map(|r| format!("{:>24.6e}", r.errors[i]))
+                .collect::<Vec<String>>();
+            println!("{:>12.3e} {}", p, errors.join(" "));
+        }
+        for r in &self.results {
+            match r.order {
+                Some(fit) => println!(
+                    "{}: observed order {:.3} ({} points, {} in [{:e}, {:e}])",
+                    r.name, fit.order, fit.points, self.parameter, fit.from, fit.to
+                ),
+                None => println!("{}: no asymptotic region to fit the order", r.name),
+            }
+            println!(
+                "{}: smallest error {:e} at {} = {:e}",
+                r.name, r.optimum.1, self.parameter, r.optimum.0
+            );
+        }
+    }
+
+    // Errors of all methods against the parameter on log-log axes, with the
+    // optimum of every method marked
+    pub fn plot(&self) {
+        let mut plot = Plot::new();
+        for r in &self.results {
+            let trace = Scatter::new(self.sweep.clone(), r.errors.clone())
+                .mode(Mode::LinesMarkers)
+                .name(&r.name);
+            let optimum = Scatter::new(vec![r.optimum.0], vec![r.optimum.1])
+                .mode(Mode::Markers)
+                .name(format!("{}, optimum", r.name));
+            plot.add_trace(trace);
+            plot.add_trace(optimum);
+        }
+        let layout = Layout::new()
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Log)
+                    .title(Title::from(format!("{} Values", self.parameter).as_str())),
+            )
+            .y_axis(
+                Axis::new()
+                    .type_(AxisType::Log)
+                    .title(Title::from("Absolute Error")),
+            )
+            .title(Title::from(self.title.as_str()));
+        plot.set_layout(layout);
+        plot.show_image(plotly::ImageFormat::JPEG, 1000, 800);
+    }
+}
@@ -0,0 +1,182 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Numbers the test functions can be written for, so that the same code runs on
+// f64 and on dual numbers
+pub trait Scalar:
+    Copy
+    + From<f64>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    // The independent variable at x, with derivative 1 on every level
+    fn variable(x: f64) -> Self;
+    fn value(&self) -> f64;
+    fn powi(self, n: i32) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn asinh(self) -> Self;
+    fn tanh(self) -> Self;
+}
+
+impl Scalar for f64 {
+    fn variable(x: f64) -> f64 {
+        x
+    }
+    fn value(&self) -> f64 {
+        *self
+    }
+    fn powi(self, n: i32) -> f64 {
+        f64::powi(self, n)
+    }
+    fn sin(self) -> f64 {
+        f64::sin(self)
+    }
+    fn cos(self) -> f64 {
+        f64::cos(self)
+    }
+    fn exp(self) -> f64 {
+        f64::exp(self)
+    }
+    fn ln(self) -> f64 {
+        f64::ln(self)
+    }
+    fn sqrt(self) -> f64 {
+        f64::sqrt(self)
+    }
+    fn asinh(self) -> f64 {
+        f64::asinh(self)
+    }
+    fn tanh(self) -> f64 {
+        f64::tanh(self)
+    }
+}
+
+// a + b eps with eps^2 = 0, so f(x + eps) = f(x) + f'(x) eps.
+// Nesting duals, Dual<Dual<f64>>, gives higher derivatives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual<T> {
+    pub value: T,
+    pub derivative: T,
+}
+
+impl<T: Scalar> Dual<T> {
+    pub fn new(value: T, derivative: T) -> Dual<T> {
+        Dual { value, derivative }
+    }
+
+    pub fn constant(value: T) -> Dual<T> {
+        Dual::new(value, T::from(0.0))
+    }
+
+    // f(a + b eps) = f(a) + f'(a) b eps
+    fn chain(self, value: T, derivative: T) -> Dual<T> {
+        Dual::new(value, derivative * self.derivative)
+    }
+}
+
+impl<T: Scalar> From<f64> for Dual<T> {
+    fn from(value: f64) -> Dual<T> {
+        Dual::constant(T::from(value))
+    }
+}
+
+impl<T: Scalar> Add for Dual<T> {
+    type Output = Dual<T>;
+    fn add(self, other: Dual<T>) -> Dual<T> {
+        Dual::new(self.value + other.value, self.derivative + other.derivative)
+    }
+}
+
+impl<T: Scalar> Sub for Dual<T> {
+    type Output = Dual<T>;
+    fn sub(self, other: Dual<T>) -> Dual<T> {
+        Dual::new(self.value - other.value, self.derivative - other.derivative)
+    }
+}
+
+impl<T: Scalar> Mul for Dual<T> {
+    type Output = Dual<T>;
+    fn mul(self, other: Dual<T>) -> Dual<T> {
+        Dual::new(
+            self.value * other.value,
+            self.derivative * other.value + self.value * other.derivative,
+        )
+    }
+}
+
+impl<T: Scalar> Div for Dual<T> {
+    type Output = Dual<T>;
+    fn div(self, other: Dual<T>) -> Dual<T> {
+        Dual::new(
+            self.value / other.value,
+            (self.derivative * other.value - self.value * other.derivative)
+                / (other.value * other.value),
+        )
+    }
+}
+
+impl<T: Scalar> Neg for Dual<T> {
+    type Output = Dual<T>;
+    fn neg(self) -> Dual<T> {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+impl<T: Scalar> Scalar for Dual<T> {
+    fn variable(x: f64) -> Dual<T> {
+        Dual::new(T::variable(x), T::from(1.0))
+    }
+    fn value(&self) -> f64 {
+        self.value.value()
+    }
+    fn powi(self, n: i32) -> Dual<T> {
+        if n == 0 {
+            return Dual::from(1.0);
+        }
+        let derivative = T::from(n as f64) * self.value.powi(n - 1);
+        self.chain(self.value.powi(n), derivative)
+    }
+    fn sin(self) -> Dual<T> {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+    fn cos(self) -> Dual<T> {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+    fn exp(self) -> Dual<T> {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+    fn ln(self) -> Dual<T> {
+        self.chain(self.value.ln(), T::from(1.0) / self.value)
+    }
+    fn sqrt(self) -> Dual<T> {
+        let s = self.value.sqrt();
+        self.chain(s, T::from(0.5) / s)
+    }
+    fn asinh(self) -> Dual<T> {
+        let derivative = T::from(1.0) / (self.value * self.value + T::from(1.0)).sqrt();
+        self.chain(self.value.asinh(), derivative)
+    }
+    fn tanh(self) -> Dual<T> {
+        let t = self.value.tanh();
+        self.chain(t, T::from(1.0) - t * t)
+    }
+}
+
+pub fn derivative(f: impl Fn(Dual<f64>) -> Dual<f64>, x: f64) -> f64 {
+    f(Dual::variable(x)).derivative
+}
+
+pub fn second_derivative(f: impl Fn(Dual<Dual<f64>>) -> Dual<Dual<f64>>, x: f64) -> f64 {
+    f(Dual::variable(x)).derivative.derivative
+}
+
+pub fn third_derivative(f: impl Fn(Dual<Dual<Dual<f64>>>) -> Dual<Dual<Dual<f64>>>, x: f64) -> f64 {
+    f(Dual::variable(x)).derivative.derivative.derivative
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientError {
+    Empty,
+    DimensionMismatch { expected: usize, found: usize },
+    NonFinite { component: usize },
+    InvalidTypical { component: usize },
+}
+
+impl fmt::Display for GradientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GradientError::Empty => write!(f, "the point has no coordinates"),
+            GradientError::DimensionMismatch { expected, found } => {
+                write!(f, "expected {} components, found {}", expected, found)
+            }
+            GradientError::NonFinite { component } => {
+                write!(f, "component {} is not finite", component)
+            }
+            GradientError::InvalidTypical { component } => write!(
+                f,
+                "the typical magnitude of component {} has to be finite and nonzero",
+                component
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GradientError {}
+
+// Step for every coordinate: relative * max(|x_i|, typical_i). The typical
+// magnitudes (1 when not given) keep the step sensible for coordinates that
+// happen to be near zero but usually are large, or the other way round.
+// The steps are made exactly representable, so that (x + h) - x = h.
+pub fn coordinate_steps(
+    x: &[f64],
+    typical: Option<&[f64]>,
+    relative: f64,
+) -> Result<Vec<f64>, GradientError> {
+    if x.is_empty() {
+        return Err(GradientError::Empty);
+    }
+    if let Some(typical) = typical {
+        if typical.len() != x.len() {
+            return Err(GradientError::DimensionMismatch {
+                expected: x.len(),
+                found: typical.len(),
+            });
+        }
+        // A zero magnitude would give h = 0 for a coordinate at zero
+        if let Some(component) = typical.iter().position(|t| *t == 0.0 || !t.is_finite()) {
+            return Err(GradientError::InvalidTypical { component });
+        }
+    }
+    x.iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            if !xi.is_finite() {
+                return Err(GradientError::NonFinite { component: i });
+            }
+            let scale = typical.map_or(1.0, |t| t[i].abs());
+            let h = relative * xi.abs().max(scale);
+            Ok((xi + h) - xi)
+        })
+        .collect()
+}
+
+// Central differences, h_i ~ eps^(1/3) balances the O(h^2) truncation against round-off
+pub fn gradient(
+    f: impl Fn(&[f64]) -> f64,
+    x: &[f64],
+    typical: Option<&[f64]>,
+) -> Result<Vec<f64>, GradientError> {
+    let steps = coordinate_steps(x, typical, f64::EPSILON.cbrt())?;
+    let mut xh = x.to_vec();
+    Ok(steps
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            xh[i] = x[i] + h;
+            let forward = f(&xh);
+            xh[i] = x[i] - h;
+            let backward = f(&xh);
+            xh[i] = x[i];
+            (forward - backward) / (2.0 * h)
+        })
+        .collect())
+}
+
+// J[i][j] = dF_i / dx_j, one column per pair of evaluations
+pub fn jacobian(
+    f: impl Fn(&[f64]) -> Vec<f64>,
+    x: &[f64],
+    typical: Option<&[f64]>,
+) -> Result<Vec<Vec<f64>>, GradientError> {
+    let steps = coordinate_steps(x, typical, f64::EPSILON.cbrt())?;
+    let m = f(x).len();
+    let mut jacobian = vec![vec![0.0; x.len()]; m];
+    let mut xh = x.to_vec();
+    for (j, h) in steps.iter().enumerate() {
+        xh[j] = x[j] + h;
+        let forward = f(&xh);
+        xh[j] = x[j] - h;
+        let backward = f(&xh);
+        xh[j] = x[j];
+        for values in [&forward, &backward] {
+            if values.len() != m {
+                return Err(GradientError::DimensionMismatch {
+                    expected: m,
+                    found: values.len(),
+                });
+            }
+        }
+        for (row, (a, b)) in jacobian.iter_mut().zip(forward.iter().zip(&backward)) {
+            row[j] = (a - b) / (2.0 * h);
+        }
+    }
+    Ok(jacobian)
+}
+
+// Second differences with h_i ~ eps^(1/4); the mixed derivatives use the four
+// points (x_i +- h_i, x_j +- h_j). The result is symmetric by construction.
+pub fn hessian(
+    f: impl Fn(&[f64]) -> f64,
+    x: &[f64],
+    typical: Option<&[f64]>,
+) -> Result<Vec<Vec<f64>>, GradientError> {
+    let steps = coordinate_steps(x, typical, f64::EPSILON.powf(0.25))?;
+    let n = x.len();
+    let fx = f(x);
+    let mut hessian = vec![vec![0.0; n]; n];
+    let mut xh = x.to_vec();
+    for i in 0..n {
+        let hi = steps[i];
+        xh[i] = x[i] + hi;
+        let forward = f(&xh);
+        xh[i] = x[i] - hi;
+        let backward = f(&xh);
+        xh[i] = x[i];
+        hessian[i][i] = (forward - 2.0 * fx + backward) / (hi * hi);
+
+        for j in 0..i {
+            let hj = steps[j];
+            let mut corner = |si: f64, sj: f64| {
+                xh[i] = x[i] + si * hi;
+                xh[j] = x[j] + sj * hj;
+                let value = f(&xh);
+                xh[i] = x[i];
+                xh[j] = x[j];
+                value
+            };
+            let mixed = (corner(1.0, 1.0) - corner(1.0, -1.0) - corner(-1.0, 1.0)
+                + corner(-1.0, -1.0))
+                / (4.0 * hi * hj);
+            hessian[i][j] = mixed;
+            hessian[j][i] = mixed;
+        }
+    }
+    Ok(hessian)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mismatch {
+    pub component: usize,
+    pub provided: f64,
+    pub numerical: f64,
+    // |provided - numerical| / max(|provided|, |numerical|, 1)
+    pub error: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GradientCheck {
+    pub numerical: Vec<f64>,
+    pub errors: Vec<f64>,
+    // Components whose error exceeds the tolerance
+    pub mismatches: Vec<Mismatch>,
+    pub tolerance: f64,
+}
+
+impl GradientCheck {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn max_error(&self) -> f64 {
+        self.errors.iter().fold(0.0, |m, e| m.max(*e))
+    }
+
+    pub fn print(&self) {
+        if self.passed() {
+            println!(
+                "The gradient is correct, largest error {:e} (tolerance {:e})",
+                self.max_error(),
+                self.tolerance
+            );
+            return;
+        }
+        println!(
+            "The gradient is incorrect in {} of {} components (tolerance {:e}):",
+            self.mismatches.len(),
+            self.numerical.len(),
+            self.tolerance
+        );
+        for m in &self.mismatches {
+            println!(
+                "  component {}: provided {}, numerical {}, error {:e}",
+                m.component, m.provided, m.numerical, m.error
+            );
+        }
+    }
+}
+
+// Compares a gradient written by hand with central differences at x. The
+// central difference is accurate to about eps^(2/3), so tolerances around
+// 1e-6 separate wrong formulas from finite difference error.
+pub fn check_gradient(
+    f: impl Fn(&[f64]) -> f64,
+    grad: impl Fn(&[f64]) -> Vec<f64>,
+    x: &[f64],
+    typical: Option<&[f64]>,
+    tolerance: f64,
+) -> Result<GradientCheck, GradientError> {
+    let numerical = gradient(f, x, typical)?;
+    let provided = grad(x);
+    if provided.len() != numerical.len() {
+        return Err(GradientError::DimensionMismatch {
+            expected: numerical.len(),
+            found: provided.len(),
+        });
+    }
+    let errors = provided
+        .iter()
+        .zip(&numerical)
+        .map(|(p, n)| (p - n).abs() / p.abs().max(n.abs()).max(1.0))
+        .collect::<Vec<f64>>();
+    let mismatches = errors
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.is_nan() || **e > tolerance)
+        .map(|(i, e)| Mismatch {
+            component: i,
+            provided: provided[i],
+            numerical: numerical[i],
+            error: *e,
+        })
+        .collect();
+    Ok(GradientCheck {
+        numerical,
+        errors,
+        mismatches,
+        tolerance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // f = x0^2 + 3 x0 x1 + 2 x1^2 - x2, with a constant Hessian
+    fn quadratic(x: &[f64]) -> f64 {
+        x[0] * x[0] + 3.0 * x[0] * x[1] + 2.0 * x[1] * x[1] - x[2]
+    }
+
+    fn quadratic_gradient(x: &[f64]) -> Vec<f64> {
+        vec![2.0 * x[0] + 3.0 * x[1], 3.0 * x[0] + 4.0 * x[1], -1.0]
+    }
+
+    #[test]
+    fn gradient_of_quadratic() {
+        let x = [1.5, -2.0, 4.0];
+        let numerical = gradient(quadratic, &x, None).unwrap();
+        for (n, e) in numerical.iter().zip(quadratic_gradient(&x)) {
+            assert!((n - e).abs() <= 1e-8, "{} differs from {}", n, e);
+        }
+    }
+
+    #[test]
+    fn jacobian_of_gradient_is_hessian() {
+        let x = [1.5, -2.0, 4.0];
+        let expected = [[2.0, 3.0, 0.0], [3.0, 4.0, 0.0], [0.0, 0.0, 0.0]];
+        let jacobian = jacobian(quadratic_gradient, &x, None).unwrap();
+        let hessian = hessian(quadratic, &x, None).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((jacobian[i][j] - expected[i][j]).abs() <= 1e-8);
+                assert!((hessian[i][j] - expected[i][j]).abs() <= 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn check_gradient_finds_wrong_component() {
+        let x = [1.5, -2.0, 4.0];
+        let correct = check_gradient(quadratic, quadratic_gradient, &x, None, 1e-6).unwrap();
+        assert!(correct.passed());
+        let wrong = |x: &[f64]| {
+            let mut g = quadratic_gradient(x);
+            g[1] = 3.0 * x[0] + 2.0 * x[1];
+            g
+        };
+        let check = check_gradient(quadratic, wrong, &x, None, 1e-6).unwrap();
+        assert!(!check.passed());
+        assert_eq!(check.mismatches.len(), 1);
+        assert_eq!(check.mismatches[0].component, 1);
+    }
+
+    #[test]
+    fn zero_typical_magnitude_is_rejected() {
+        let result = gradient(quadratic, &[0.0, 1.0, 1.0], Some(&[0.0, 1.0, 1.0]));
+        assert_eq!(result, Err(GradientError::InvalidTypical { component: 0 }));
+    }
+}
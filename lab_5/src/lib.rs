@@ -0,0 +1,8 @@
+pub mod complex_step;
+pub mod convergence;
+pub mod dual;
+pub mod gradient;
+pub mod richardson;
+pub mod sampled;
+pub mod stencil;
+pub mod step_size;
@@ -1,48 +1,13 @@
-use plotly::common::{Mode, Title};
-use plotly::layout::{Axis, AxisType, Layout};
-use plotly::{Plot, Scatter};
-
-fn plot_error_vs_h(
-    h_values: Vec<f64>, 
-    abs_errs_central: Vec<f64>,
-    abs_errs_forward: Vec<f64>, 
-    opt_h_cent: f64, 
-    opt_err_cent: f64,
-    title: &str
-    ) {
-    let trace_central = Scatter::new(h_values.clone(), abs_errs_central.clone()).mode(Mode::LinesMarkers).name("Central");
-    let trace_forward = Scatter::new(h_values.clone(), abs_errs_forward.clone()).mode(Mode::LinesMarkers).name("Forward");
-    let trace_optimal = Scatter::new(vec![opt_h_cent], vec![opt_err_cent]).mode(Mode::Markers).name("Optimal");
-    let layout = Layout::new()
-        .x_axis(Axis::new().type_(AxisType::Log).title(Title::from("h Values")))
-        .y_axis(Axis::new().type_(AxisType::Log).title(Title::from("Absolute Error")))
-        .title(Title::from(title));
-    let mut plot_cfg = Plot::new();
-    plot_cfg.add_trace(trace_central);
-    plot_cfg.add_trace(trace_forward);
-    plot_cfg.add_trace(trace_optimal);
-    plot_cfg.set_layout(layout);
-    plot_cfg.show_image(plotly::ImageFormat::JPEG, 1000, 800);
-}
-
-fn plot_error_vs_h_second_der(
-    h_values: Vec<f64>,
-    abs_err: Vec<f64>,
-    title: &str
-) {
-    let trace = Scatter::new(h_values.clone(), abs_err.clone()).mode(Mode::LinesMarkers);
-    let layout = Layout::new()
-        .x_axis(Axis::new().type_(AxisType::Log).title(Title::from("h Values")))
-        .y_axis(Axis::new().type_(AxisType::Log).title(Title::from("Absolute Error")))
-        .title(Title::from(title));
-    let mut plot_cfg = Plot::new();
-    plot_cfg.add_trace(trace);
-    plot_cfg.set_layout(layout);
-    plot_cfg.show_image(plotly::ImageFormat::JPEG, 1000, 800);
-}
+use lab_5::convergence::{ConvergenceStudy, Parameter};
+use lab_5::dual::{self, Scalar};
+use lab_5::gradient::{check_gradient, gradient, hessian, jacobian, GradientError};
+use lab_5::richardson::richardson_derivative;
+use lab_5::sampled::{differentiate, savitzky_golay, Points, SampledError};
+use lab_5::stencil::Stencil;
+use lab_5::step_size::{optimal_step, Formula};
 
 fn two_point_central_der(f: fn(f64) -> f64, x: f64, h: f64) -> f64 {
-    (f(x + h/2.0) - f(x - h/2.0)) / h
+    (f(x + h / 2.0) - f(x - h / 2.0)) / h
 }
 
 fn two_point_foward_der(function: fn(f64) -> f64, x: f64, h: f64) -> f64 {
@@ -50,74 +15,302 @@ fn two_point_foward_der(function: fn(f64) -> f64, x: f64, h: f64) -> f64 {
 }
 
 fn second_derivative(f: fn(f64) -> f64, x: f64, h: f64) -> f64 {
-    4.0*(f(x + h/2.0)+f(x-h/2.0)-2.0*f(x))/h.powi(2) 
+    4.0 * (f(x + h / 2.0) + f(x - h / 2.0) - 2.0 * f(x)) / h.powi(2)
 }
 
 fn absolute_error(analytical: f64, numerical: f64) -> f64 {
     (analytical - numerical).abs()
 }
 
-fn h_optimal_central() -> f64 {
-    std::f64::EPSILON.powf(1.0 / 3.0)
+fn function_1<T: Scalar>(x: T) -> T {
+    (T::from(2.0) * x).sin().exp()
 }
 
-fn function_1(x: f64) -> f64 {
-    std::f64::consts::E.powf((2.0*x).sin())
+fn function_2<T: Scalar>(x: T) -> T {
+    x.powi(3) - T::from(2.0) * x.powi(2) + T::from(3.0) * x - T::from(1.0)
 }
 
-fn analytical_derivative_1(x: f64) -> f64 {
-    2.0 * (2.0*x).cos() * std::f64::consts::E.powf((2.0*x).sin())
+fn task_1(x: f64, h_values: Vec<f64>) {
+    let study = ConvergenceStudy::new(
+        "First derrivative of e^(sin(2x))",
+        Parameter::Step,
+        h_values,
+        dual::derivative(function_1, x),
+    )
+    .method("Central", |h| two_point_central_der(function_1, x, h))
+    .method("Forward", |h| two_point_foward_der(function_1, x, h))
+    .run();
+    study.print();
+    println!(
+        "Predicted optimal h for central difference: {}",
+        optimal_step(function_1, x, Formula::Central).h
+    );
+    study.plot();
 }
 
-fn analytical_second_derivative_1(x: f64) -> f64 {
-    let sin_2x = (2.0*x).sin();
-    let cos_2x = (2.0*x).cos();
-    4.0 * (cos_2x.powi(2) - sin_2x) * std::f64::consts::E.powf(sin_2x)
+fn task_2(x: f64, h_values: Vec<f64>) {
+    let study = ConvergenceStudy::new(
+        "Second derrivative of e^(sin(2x))",
+        Parameter::Step,
+        h_values,
+        dual::second_derivative(function_1, x),
+    )
+    .method("Second difference", |h| second_derivative(function_1, x, h))
+    .run();
+    study.print();
+    study.plot();
 }
 
-fn function_2(x: f64) -> f64 {
-    x.powi(3) - 2.0*x.powi(2) + 3.0*x - 1.0
+fn task_3(x: f64, h_values: Vec<f64>) {
+    let study = ConvergenceStudy::new(
+        "First derrivative of x^3 - 2x^2 + 3x - 1",
+        Parameter::Step,
+        h_values,
+        dual::derivative(function_2, x),
+    )
+    .method("Central", |h| two_point_central_der(function_2, x, h))
+    .method("Forward", |h| two_point_foward_der(function_2, x, h))
+    .run();
+    study.print();
+    println!(
+        "Predicted optimal h for central difference: {}",
+        optimal_step(function_2, x, Formula::Central).h
+    );
+    study.plot();
+}
+
+fn task_4(x: f64, h_values: Vec<f64>) {
+    let analytical = dual::derivative(function_1, x);
+    let best_central = h_values
+        .iter()
+        .map(|h| absolute_error(analytical, two_point_central_der(function_1, x, *h)))
+        .fold(f64::INFINITY, f64::min);
+    let richardson = richardson_derivative(function_1, x, 0.5, 12);
+    println!("Richardson derivative: {}", richardson.value);
+    println!("Estimated error: {:e}", richardson.error);
+    println!(
+        "Absolute error: {:e}",
+        absolute_error(analytical, richardson.value)
+    );
+    println!(
+        "Smallest step: {}, central differences used: {}",
+        richardson.h, richardson.steps
+    );
+    println!(
+        "Best absolute error of the central difference over h: {:e}",
+        best_central
+    );
+}
+
+fn task_5(x: f64) {
+    let stencils = [
+        (
+            "3rd derivative, central, 4th order",
+            Stencil::central(3, 4),
+            dual::third_derivative(function_1, x),
+            1e-2,
+        ),
+        (
+            "1st derivative, forward, 6th order",
+            Stencil::forward(1, 6),
+            dual::derivative(function_1, x),
+            1e-2,
+        ),
+        (
+            "1st derivative, backward, 6th order",
+            Stencil::backward(1, 6),
+            dual::derivative(function_1, x),
+            1e-2,
+        ),
+        (
+            "2nd derivative, arbitrary offsets",
+            Stencil::new(2, vec![-1.0, 0.0, 0.5, 2.0]),
+            dual::second_derivative(function_1, x),
+            1e-3,
+        ),
+    ];
+    for (name, stencil, analytical, h) in stencils {
+        match stencil {
+            Ok(stencil) => {
+                let numerical = stencil.evaluate(function_1, x, h);
+                println!("{} (O(h^{})): {}", name, stencil.accuracy(), stencil);
+                println!(
+                    "  h = {}, value = {}, absolute error = {:e}",
+                    h,
+                    numerical,
+                    absolute_error(analytical, numerical)
+                );
+            }
+            Err(e) => println!("{}: {}", name, e),
+        }
+    }
+}
+
+fn print_optimal_step(
+    name: &str,
+    f: fn(f64) -> f64,
+    derivative: impl Fn(f64) -> f64,
+    formula: Formula,
+    analytical: f64,
+    x: f64,
+) {
+    let step = optimal_step(f, x, formula);
+    let numerical = derivative(step.h);
+    println!(
+        "{}: h = {:e}, predicted error = {:e}, actual error = {:e}",
+        name,
+        step.h,
+        step.total_error,
+        absolute_error(analytical, numerical)
+    );
 }
 
-fn analytical_derivative_2(x: f64) -> f64 {
-    3.0*x.powi(2) - 4.0*x + 3.0
+fn task_6(x: f64) {
+    print_optimal_step(
+        "e^(sin(2x)), forward",
+        function_1,
+        |h| two_point_foward_der(function_1, x, h),
+        Formula::Forward,
+        dual::derivative(function_1, x),
+        x,
+    );
+    print_optimal_step(
+        "e^(sin(2x)), central",
+        function_1,
+        |h| two_point_central_der(function_1, x, h),
+        Formula::Central,
+        dual::derivative(function_1, x),
+        x,
+    );
+    print_optimal_step(
+        "e^(sin(2x)), second",
+        function_1,
+        |h| second_derivative(function_1, x, h),
+        Formula::Second,
+        dual::second_derivative(function_1, x),
+        x,
+    );
+    print_optimal_step(
+        "x^3 - 2x^2 + 3x - 1, forward",
+        function_2,
+        |h| two_point_foward_der(function_2, x, h),
+        Formula::Forward,
+        dual::derivative(function_2, x),
+        x,
+    );
+    print_optimal_step(
+        "x^3 - 2x^2 + 3x - 1, central",
+        function_2,
+        |h| two_point_central_der(function_2, x, h),
+        Formula::Central,
+        dual::derivative(function_2, x),
+        x,
+    );
 }
 
-fn task_1(x: f64, h_values: Vec<f64>){
-    let analytical = analytical_derivative_1(x);
-    let num_dfxs_central = h_values.iter().map(|h| two_point_central_der(function_1, x, *h)).collect::<Vec<f64>>();
-    let num_dfxs_forward = h_values.iter().map(|h| two_point_foward_der(function_1, x, *h)).collect::<Vec<f64>>();
-    let abs_errs_central = num_dfxs_central.iter().map(|num_dfx| absolute_error(analytical, *num_dfx)).collect::<Vec<f64>>();
-    let abs_errs_forward = num_dfxs_forward.iter().map(|num_dfx| absolute_error(analytical, *num_dfx)).collect::<Vec<f64>>();
-    let h_optimal = h_optimal_central();
-    let num_dfx_optimal = two_point_central_der(function_1, x, h_optimal);
-    let abs_err_optimal = absolute_error(analytical, num_dfx_optimal);
-    println!("Optimal h for central difference: {}", h_optimal);
-    println!("Optimal numerical derivative: {}", num_dfx_optimal);
-    println!("Optimal absolute error: {}", abs_err_optimal);
-    plot_error_vs_h(h_values, abs_errs_central, abs_errs_forward, h_optimal, abs_err_optimal, "First derrivative of e^(sin(2x))");
+fn rosenbrock(p: &[f64]) -> f64 {
+    100.0 * (p[1] - p[0].powi(2)).powi(2) + (1.0 - p[0]).powi(2)
 }
 
-fn task_2(x: f64, h_values: Vec<f64>){
-    let analytical = analytical_second_derivative_1(x);
-    let num_dfxs = h_values.iter().map(|h| second_derivative(function_1, x, *h)).collect::<Vec<f64>>();
-    let abs_errs = num_dfxs.iter().map(|num_dfx| absolute_error(analytical, *num_dfx)).collect::<Vec<f64>>();
-    plot_error_vs_h_second_der(h_values, abs_errs, "Second derrivative of e^(sin(2x))");
+fn rosenbrock_gradient(p: &[f64]) -> Vec<f64> {
+    vec![
+        -400.0 * p[0] * (p[1] - p[0].powi(2)) - 2.0 * (1.0 - p[0]),
+        200.0 * (p[1] - p[0].powi(2)),
+    ]
 }
 
-fn task_3(x: f64, h_values: Vec<f64>){
-    let analytical = analytical_derivative_2(x);
-    let num_dfxs_central = h_values.iter().map(|h| two_point_central_der(function_2, x, *h)).collect::<Vec<f64>>();
-    let num_dfxs_forward = h_values.iter().map(|h| two_point_foward_der(function_2, x, *h)).collect::<Vec<f64>>();
-    let abs_errs_central = num_dfxs_central.iter().map(|num_dfx| absolute_error(analytical, *num_dfx)).collect::<Vec<f64>>();
-    let abs_errs_forward = num_dfxs_forward.iter().map(|num_dfx| absolute_error(analytical, *num_dfx)).collect::<Vec<f64>>();
-    let h_optimal = h_optimal_central();
-    let num_dfx_optimal = two_point_central_der(function_2, x, h_optimal);
-    let abs_err_optimal = absolute_error(analytical, num_dfx_optimal);
-    println!("Optimal h for central difference: {}", h_optimal);
-    println!("Optimal numerical derivative: {}", num_dfx_optimal);
-    println!("Optimal absolute error: {}", abs_err_optimal);
-    plot_error_vs_h(h_values, abs_errs_central, abs_errs_forward, h_optimal, abs_err_optimal, "First derrivative of x^3 - 2x^2 + 3x - 1");
+// Same gradient with the chain rule factor 2 missing in the first component
+fn rosenbrock_gradient_wrong(p: &[f64]) -> Vec<f64> {
+    vec![
+        -200.0 * p[0] * (p[1] - p[0].powi(2)) - 2.0 * (1.0 - p[0]),
+        200.0 * (p[1] - p[0].powi(2)),
+    ]
+}
+
+fn task_7() -> Result<(), GradientError> {
+    let p = [-1.2, 1.0];
+    println!("Rosenbrock gradient: {:?}", gradient(rosenbrock, &p, None)?);
+    println!("Exact gradient:      {:?}", rosenbrock_gradient(&p));
+    println!("Rosenbrock Hessian: {:?}", hessian(rosenbrock, &p, None)?);
+    println!(
+        "Exact Hessian:      {:?}",
+        [
+            [1200.0 * p[0].powi(2) - 400.0 * p[1] + 2.0, -400.0 * p[0]],
+            [-400.0 * p[0], 200.0]
+        ]
+    );
+    for (name, grad) in [
+        ("correct", rosenbrock_gradient as fn(&[f64]) -> Vec<f64>),
+        ("wrong", rosenbrock_gradient_wrong),
+    ] {
+        print!("Checking the {} gradient: ", name);
+        check_gradient(rosenbrock, grad, &p, None, 1e-6)?.print();
+    }
+
+    // Coordinates of very different size: a position in metres and an angle.
+    // The typical magnitudes keep the step for the position large even at 0.
+    let f = |q: &[f64]| vec![q[0] * q[1].cos(), q[0] * q[1].sin(), (q[0] / 1000.0).exp()];
+    let q = [0.0, 0.3];
+    println!("Jacobian: {:?}", jacobian(f, &q, Some(&[1000.0, 1.0]))?);
+    Ok(())
+}
+
+fn max_error(exact: &[f64], numerical: &[f64]) -> f64 {
+    exact
+        .iter()
+        .zip(numerical)
+        .map(|(a, n)| absolute_error(*a, *n))
+        .fold(0.0, f64::max)
+}
+
+// e^(sin(2x)) sampled on [0, 2] more densely towards 0, once exactly and once
+// with measurement noise of amplitude 1e-3
+fn task_8() -> Result<(), SampledError> {
+    let n = 41;
+    let x = (0..n)
+        .map(|i| 2.0 * (i as f64 / (n - 1) as f64).powf(1.5))
+        .collect::<Vec<f64>>();
+    let y = x.iter().map(|xi| function_1(*xi)).collect::<Vec<f64>>();
+    let exact = x
+        .iter()
+        .map(|xi| dual::derivative(function_1, *xi))
+        .collect::<Vec<f64>>();
+    let exact_second = x
+        .iter()
+        .map(|xi| dual::second_derivative(function_1, *xi))
+        .collect::<Vec<f64>>();
+    println!(
+        "Sampled data, 3 points: max error of f' = {:e}",
+        max_error(&exact, &differentiate(&x, &y, 1, Points::Three)?)
+    );
+    println!(
+        "Sampled data, 5 points: max error of f' = {:e}",
+        max_error(&exact, &differentiate(&x, &y, 1, Points::Five)?)
+    );
+    println!(
+        "Sampled data, 5 points: max error of f'' = {:e}",
+        max_error(&exact_second, &differentiate(&x, &y, 2, Points::Five)?)
+    );
+
+    let noisy = y
+        .iter()
+        .enumerate()
+        .map(|(i, yi)| yi + 1e-3 * (i as f64 * 7919.0).sin())
+        .collect::<Vec<f64>>();
+    println!(
+        "Noisy data, 5 points: max error of f' = {:e}",
+        max_error(&exact, &differentiate(&x, &noisy, 1, Points::Five)?)
+    );
+    println!(
+        "Noisy data, Savitzky-Golay (7 samples, cubic): max error of f' = {:e}",
+        max_error(&exact, &savitzky_golay(&x, &noisy, 7, 3, 1)?)
+    );
+    let smoothed = savitzky_golay(&x, &noisy, 7, 3, 0)?;
+    println!(
+        "Noisy data, smoothed then 5 points: max error of f' = {:e}",
+        max_error(&exact, &differentiate(&x, &smoothed, 1, Points::Five)?)
+    );
+    Ok(())
 }
 
 fn main() {
@@ -126,4 +319,13 @@ fn main() {
     task_1(x, h_values.clone());
     task_2(x, h_values.clone());
     task_3(x, h_values.clone());
+    task_4(x, h_values.clone());
+    task_5(x);
+    task_6(x);
+    if let Err(e) = task_7() {
+        println!("Gradient check failed: {}", e);
+    }
+    if let Err(e) = task_8() {
+        println!("Differentiation of sampled data failed: {}", e);
+    }
 }
@@ -0,0 +1,65 @@
+// Step is divided by this factor between the columns of the tableau
+const STEP_DIVISOR: f64 = 2.0;
+// Stop when the error grows by more than this factor compared to the best estimate
+const SAFE: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Derivative {
+    pub value: f64,
+    // Estimated absolute error of the value
+    pub error: f64,
+    // Smallest step used to reach the value
+    pub h: f64,
+    // Number of central differences evaluated
+    pub steps: usize,
+}
+
+fn central_difference(f: &impl Fn(f64) -> f64, x: f64, h: f64) -> f64 {
+    (f(x + h) - f(x - h)) / (2.0 * h)
+}
+
+// Ridders' version of Richardson extrapolation. Central differences with
+// h, h/2, h/4, ... fill the first row of a tableau and every further row removes
+// the next even power of h from the truncation error. The entry with the smallest
+// difference to its neighbours is returned, and the tableau stops growing once
+// round-off makes the higher orders worse.
+pub fn richardson_derivative(
+    f: impl Fn(f64) -> f64,
+    x: f64,
+    h: f64,
+    max_steps: usize,
+) -> Derivative {
+    let max_steps = max_steps.max(1);
+    let mut h = h.abs();
+    let mut tableau = vec![vec![0.0; max_steps]; max_steps];
+    tableau[0][0] = central_difference(&f, x, h);
+    let mut best = Derivative {
+        value: tableau[0][0],
+        error: f64::INFINITY,
+        h,
+        steps: 1,
+    };
+
+    for i in 1..max_steps {
+        h /= STEP_DIVISOR;
+        tableau[0][i] = central_difference(&f, x, h);
+        let mut factor = STEP_DIVISOR * STEP_DIVISOR;
+        for j in 1..=i {
+            tableau[j][i] = (tableau[j - 1][i] * factor - tableau[j - 1][i - 1]) / (factor - 1.0);
+            factor *= STEP_DIVISOR * STEP_DIVISOR;
+            let error = (tableau[j][i] - tableau[j - 1][i])
+                .abs()
+                .max((tableau[j][i] - tableau[j - 1][i - 1]).abs());
+            if error <= best.error {
+                best.value = tableau[j][i];
+                best.error = error;
+                best.h = h;
+            }
+        }
+        best.steps = i + 1;
+        if (tableau[i][i] - tableau[i - 1][i - 1]).abs() >= SAFE * best.error {
+            break;
+        }
+    }
+    best
+}
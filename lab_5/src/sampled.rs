@@ -0,0 +1,192 @@
+use std::fmt;
+
+use lab_8::equation::{EquationSystem, SystemError};
+
+use crate::stencil::{Stencil, StencilError};
+
+// Number of neighbouring samples used for every derivative
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Points {
+    Three,
+    Five,
+}
+
+impl Points {
+    fn count(&self) -> usize {
+        match self {
+            Points::Three => 3,
+            Points::Five => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SampledError {
+    LengthMismatch { x: usize, y: usize },
+    TooFewSamples { needed: usize, found: usize },
+    NotIncreasing { index: usize },
+    NonFinite { index: usize },
+    EvenWindow(usize),
+    DegreeTooHigh { degree: usize, window: usize },
+    OrderTooHigh { order: usize, degree: usize },
+    Stencil(StencilError),
+    Fit(SystemError),
+}
+
+impl fmt::Display for SampledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SampledError::LengthMismatch { x, y } => {
+                write!(f, "{} x values but {} y values", x, y)
+            }
+            SampledError::TooFewSamples { needed, found } => {
+                write!(f, "at least {} samples are needed, got {}", needed, found)
+            }
+            SampledError::NotIncreasing { index } => {
+                write!(f, "x is not strictly increasing at index {}", index)
+            }
+            SampledError::NonFinite { index } => write!(f, "sample {} is not finite", index),
+            SampledError::EvenWindow(window) => {
+                write!(f, "the window has to be odd, got {}", window)
+            }
+            SampledError::DegreeTooHigh { degree, window } => write!(
+                f,
+                "a polynomial of degree {} cannot be fitted to {} samples",
+                degree, window
+            ),
+            SampledError::OrderTooHigh { order, degree } => write!(
+                f,
+                "derivative of order {} of a polynomial of degree {} is zero",
+                order, degree
+            ),
+            SampledError::Stencil(e) => write!(f, "{}", e),
+            SampledError::Fit(e) => write!(f, "the least squares fit failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SampledError {}
+
+impl From<StencilError> for SampledError {
+    fn from(e: StencilError) -> SampledError {
+        SampledError::Stencil(e)
+    }
+}
+
+impl From<SystemError> for SampledError {
+    fn from(e: SystemError) -> SampledError {
+        SampledError::Fit(e)
+    }
+}
+
+fn check_samples(x: &[f64], y: &[f64], needed: usize) -> Result<(), SampledError> {
+    if x.len() != y.len() {
+        return Err(SampledError::LengthMismatch {
+            x: x.len(),
+            y: y.len(),
+        });
+    }
+    if x.len() < needed {
+        return Err(SampledError::TooFewSamples {
+            needed,
+            found: x.len(),
+        });
+    }
+    if let Some(index) = (0..x.len()).find(|i| !x[*i].is_finite() || !y[*i].is_finite()) {
+        return Err(SampledError::NonFinite { index });
+    }
+    if let Some(index) = x.windows(2).position(|w| w[1] <= w[0]) {
+        return Err(SampledError::NotIncreasing { index: index + 1 });
+    }
+    Ok(())
+}
+
+// First index of the `width` samples around i. The window is centred where
+// possible and slides inwards at the ends, which gives one-sided formulas there.
+fn window_start(i: usize, width: usize, len: usize) -> usize {
+    i.saturating_sub(width / 2).min(len - width)
+}
+
+// Derivative of the given order at every sample. Each value comes from the
+// Fornberg weights for the actual distances to the neighbouring samples, so the
+// spacing does not have to be uniform. With uniform spacing the interior
+// values are the usual central formulas, O(h^2) for three points and O(h^4)
+// for five, and the ends lose one order.
+pub fn differentiate(
+    x: &[f64],
+    y: &[f64],
+    order: usize,
+    points: Points,
+) -> Result<Vec<f64>, SampledError> {
+    let width = points.count();
+    check_samples(x, y, width)?;
+    (0..x.len())
+        .map(|i| {
+            let start = window_start(i, width, x.len());
+            let offsets = x[start..start + width].iter().map(|xj| xj - x[i]).collect();
+            let stencil = Stencil::new(order, offsets)?;
+            Ok(stencil
+                .weights
+                .iter()
+                .zip(&y[start..start + width])
+                .map(|(w, yj)| w * yj)
+                .sum())
+        })
+        .collect()
+}
+
+// Savitzky-Golay filter for arbitrary spacing: around every sample a polynomial
+// of the given degree is fitted by least squares to `window` samples and its
+// derivative of the given order is evaluated there. Order 0 returns the
+// smoothed signal. With a window wider than the degree + 1 the fit averages out
+// noise that finite differences would amplify.
+pub fn savitzky_golay(
+    x: &[f64],
+    y: &[f64],
+    window: usize,
+    degree: usize,
+    order: usize,
+) -> Result<Vec<f64>, SampledError> {
+    if window.is_multiple_of(2) {
+        return Err(SampledError::EvenWindow(window));
+    }
+    if degree >= window {
+        return Err(SampledError::DegreeTooHigh { degree, window });
+    }
+    if order > degree {
+        return Err(SampledError::OrderTooHigh { order, degree });
+    }
+    check_samples(x, y, window)?;
+    let factorial = (1..=order).map(|k| k as f64).product::<f64>();
+    (0..x.len())
+        .map(|i| {
+            let start = window_start(i, window, x.len());
+            let xs = &x[start..start + window];
+            let ys = &y[start..start + window];
+            // Distances scaled to [-1, 1] keep the normal equations well conditioned
+            let scale = xs
+                .iter()
+                .map(|xj| (xj - x[i]).abs())
+                .fold(f64::MIN_POSITIVE, f64::max);
+            let powers = xs
+                .iter()
+                .map(|xj| {
+                    let t = (xj - x[i]) / scale;
+                    (0..=degree).map(|k| t.powi(k as i32)).collect::<Vec<f64>>()
+                })
+                .collect::<Vec<Vec<f64>>>();
+            let normal = (0..=degree)
+                .map(|j| {
+                    (0..=degree)
+                        .map(|k| powers.iter().map(|p| p[j] * p[k]).sum())
+                        .collect()
+                })
+                .collect();
+            let rhs = (0..=degree)
+                .map(|j| powers.iter().zip(ys).map(|(p, yj)| p[j] * yj).sum())
+                .collect();
+            let coefficients = EquationSystem::from_matrix(normal, rhs)?.solve_cholesky()?;
+            Ok(factorial * coefficients[order] / scale.powi(order as i32))
+        })
+        .collect()
+}
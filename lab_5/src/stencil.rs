@@ -0,0 +1,183 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StencilError {
+    TooFewPoints { order: usize, points: usize },
+    DuplicateOffset(f64),
+    NonFiniteOffset(f64),
+    OddCentralAccuracy(usize),
+    ZeroAccuracy,
+}
+
+impl fmt::Display for StencilError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StencilError::TooFewPoints { order, points } => write!(
+                f,
+                "a derivative of order {} needs at least {} points, got {}",
+                order,
+                order + 1,
+                points
+            ),
+            StencilError::DuplicateOffset(offset) => write!(f, "offset {} is repeated", offset),
+            StencilError::NonFiniteOffset(offset) => write!(f, "offset {} is not finite", offset),
+            StencilError::OddCentralAccuracy(accuracy) => {
+                write!(f, "central stencils have even accuracy, got {}", accuracy)
+            }
+            StencilError::ZeroAccuracy => write!(f, "the accuracy has to be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for StencilError {}
+
+// Finite difference formula f^(m)(x) ~ sum w_i f(x + o_i h) / h^m
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stencil {
+    pub order: usize,
+    pub offsets: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+
+impl Stencil {
+    // Weights for any set of distinct offsets, computed with Fornberg's algorithm
+    pub fn new(order: usize, offsets: Vec<f64>) -> Result<Stencil, StencilError> {
+        if offsets.len() <= order {
+            return Err(StencilError::TooFewPoints {
+                order,
+                points: offsets.len(),
+            });
+        }
+        for (i, o) in offsets.iter().enumerate() {
+            if !o.is_finite() {
+                return Err(StencilError::NonFiniteOffset(*o));
+            }
+            if offsets[..i].contains(o) {
+                return Err(StencilError::DuplicateOffset(*o));
+            }
+        }
+        let weights = fornberg_weights(order, &offsets);
+        Ok(Stencil {
+            order,
+            offsets,
+            weights,
+        })
+    }
+
+    // Symmetric stencil, `accuracy` has to be even
+    pub fn central(order: usize, accuracy: usize) -> Result<Stencil, StencilError> {
+        if accuracy == 0 {
+            return Err(StencilError::ZeroAccuracy);
+        }
+        if accuracy % 2 == 1 {
+            return Err(StencilError::OddCentralAccuracy(accuracy));
+        }
+        let radius = (order.div_ceil(2) + accuracy / 2 - 1) as i64;
+        Stencil::new(order, (-radius..=radius).map(|i| i as f64).collect())
+    }
+
+    // One-sided stencil using x, x + h, x + 2h, ...
+    pub fn forward(order: usize, accuracy: usize) -> Result<Stencil, StencilError> {
+        if accuracy == 0 {
+            return Err(StencilError::ZeroAccuracy);
+        }
+        Stencil::new(order, (0..order + accuracy).map(|i| i as f64).collect())
+    }
+
+    // One-sided stencil using x, x - h, x - 2h, ...
+    pub fn backward(order: usize, accuracy: usize) -> Result<Stencil, StencilError> {
+        if accuracy == 0 {
+            return Err(StencilError::ZeroAccuracy);
+        }
+        Stencil::new(
+            order,
+            (0..order + accuracy).map(|i| 0.0 - i as f64).collect(),
+        )
+    }
+
+    // Order of the truncation error O(h^p), found from the first moment
+    // sum w_i o_i^k / k! beyond the derivative order that does not vanish
+    pub fn accuracy(&self) -> usize {
+        let scale = self
+            .weights
+            .iter()
+            .zip(&self.offsets)
+            .map(|(w, o)| (w * o.abs().max(1.0).powi(self.order as i32)).abs())
+            .sum::<f64>();
+        let mut factorial = (1..=self.order).map(|k| k as f64).product::<f64>();
+        for k in (self.order + 1)..(self.order + 2 * self.offsets.len()) {
+            factorial *= k as f64;
+            let moment: f64 = self
+                .weights
+                .iter()
+                .zip(&self.offsets)
+                .map(|(w, o)| w * o.powi(k as i32))
+                .sum::<f64>()
+                / factorial;
+            if moment.abs() > 1e-10 * scale {
+                return k - self.order;
+            }
+        }
+        self.offsets.len()
+    }
+
+    pub fn evaluate(&self, f: impl Fn(f64) -> f64, x: f64, h: f64) -> f64 {
+        let sum: f64 = self
+            .weights
+            .iter()
+            .zip(&self.offsets)
+            .map(|(w, o)| w * f(x + o * h))
+            .sum();
+        sum / h.powi(self.order as i32)
+    }
+}
+
+impl fmt::Display for Stencil {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = self
+            .weights
+            .iter()
+            .zip(&self.offsets)
+            .map(|(w, o)| format!("{}f(x{:+}h)", w, o))
+            .collect::<Vec<String>>();
+        write!(
+            f,
+            "f^({})(x) ~ ({}) / h^{}",
+            self.order,
+            terms.join(" + "),
+            self.order
+        )
+    }
+}
+
+// Fornberg, "Generation of finite difference formulas on arbitrarily spaced
+// grids" (1988). c[i][k] is the weight of node i in the k-th derivative at 0.
+fn fornberg_weights(order: usize, offsets: &[f64]) -> Vec<f64> {
+    let n = offsets.len();
+    let mut c = vec![vec![0.0; order + 1]; n];
+    let mut c1 = 1.0;
+    let mut c4 = offsets[0];
+    c[0][0] = 1.0;
+    for i in 1..n {
+        let mn = i.min(order);
+        let mut c2 = 1.0;
+        let c5 = c4;
+        c4 = offsets[i];
+        for j in 0..i {
+            let c3 = offsets[i] - offsets[j];
+            c2 *= c3;
+            if j == i - 1 {
+                for k in (1..=mn).rev() {
+                    c[i][k] = c1 * (k as f64 * c[i - 1][k - 1] - c5 * c[i - 1][k]) / c2;
+                }
+                c[i][0] = -c1 * c5 * c[i - 1][0] / c2;
+            }
+            for k in (1..=mn).rev() {
+                c[j][k] = (c4 * c[j][k] - k as f64 * c[j][k - 1]) / c3;
+            }
+            c[j][0] = c4 * c[j][0] / c3;
+        }
+        c1 = c2;
+    }
+    c.iter().map(|row| row[order]).collect()
+}
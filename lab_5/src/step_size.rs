@@ -0,0 +1,109 @@
+use crate::stencil::Stencil;
+
+// Spacing of the samples used to estimate the noise, relative to max(|x|, 1)
+const NOISE_SPACING: f64 = 1e-3;
+// Order of the differences used to estimate the noise
+const NOISE_ORDER: usize = 6;
+// Upper limit of the chosen step, relative to max(|x|, 1)
+const MAX_STEP: f64 = 0.1;
+
+// The formulas of lab_5, all written with the step h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formula {
+    // (f(x + h) - f(x)) / h
+    Forward,
+    // (f(x + h/2) - f(x - h/2)) / h
+    Central,
+    // 4 (f(x + h/2) + f(x - h/2) - 2 f(x)) / h^2
+    Second,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepSize {
+    pub h: f64,
+    // Predicted errors at the chosen h
+    pub truncation_error: f64,
+    pub round_off_error: f64,
+    pub total_error: f64,
+    // Estimated absolute noise of a single function value
+    pub noise: f64,
+    // Estimate of the derivative that sets the truncation error
+    pub higher_derivative: f64,
+}
+
+impl Formula {
+    // Order of the derivative in the leading truncation term
+    fn truncation_order(&self) -> usize {
+        match self {
+            Formula::Forward => 2,
+            Formula::Central => 3,
+            Formula::Second => 4,
+        }
+    }
+
+    // Truncation error is a h^p and round-off error b / h^q
+    fn error_model(&self, derivative: f64, noise: f64) -> (f64, i32, f64, i32) {
+        let d = derivative.abs();
+        match self {
+            Formula::Forward => (d / 2.0, 1, 2.0 * noise, 1),
+            Formula::Central => (d / 24.0, 2, 2.0 * noise, 1),
+            Formula::Second => (d / 48.0, 2, 16.0 * noise, 2),
+        }
+    }
+}
+
+// Estimates the noise in the values of f around x from high-order differences,
+// which remove the smooth part of f and leave the random part
+// (Hamming's difference table, as in Moré and Wild's ECnoise)
+pub fn estimate_noise(f: &impl Fn(f64) -> f64, x: f64) -> f64 {
+    let spacing = NOISE_SPACING * x.abs().max(1.0);
+    let samples = 2 * NOISE_ORDER + 1;
+    let mut differences = (0..samples)
+        .map(|i| f(x + (i as f64 - NOISE_ORDER as f64) * spacing))
+        .collect::<Vec<f64>>();
+    for _ in 0..NOISE_ORDER {
+        differences = differences.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    let mean_square = differences.iter().map(|d| d * d).sum::<f64>() / differences.len() as f64;
+    // Variance of the k-th difference of white noise is (2k)! / (k!)^2 sigma^2
+    let gamma = (1..=NOISE_ORDER).fold(1.0, |g, i| g * i as f64 / (NOISE_ORDER + i) as f64);
+    let noise = (gamma * mean_square).sqrt();
+    noise.max(f64::EPSILON * f(x).abs())
+}
+
+// Chooses h for the given formula by balancing the truncation error, estimated
+// with a central stencil for the next derivative, against the round-off error
+// caused by the noise in f. Without truncation error (e.g. a polynomial of low
+// degree) the largest allowed step is used.
+pub fn optimal_step(f: impl Fn(f64) -> f64, x: f64, formula: Formula) -> StepSize {
+    let scale = x.abs().max(1.0);
+    let noise = estimate_noise(&f, x);
+    let order = formula.truncation_order();
+
+    let relative_noise = (noise / f(x).abs().max(f64::MIN_POSITIVE)).max(f64::EPSILON);
+    let h_derivative = relative_noise.powf(1.0 / (order as f64 + 2.0)) * scale;
+    let stencil = Stencil::central(order, 2).expect("second order central stencil exists");
+    let higher_derivative = stencil.evaluate(&f, x, h_derivative);
+
+    let (a, p, b, q) = formula.error_model(higher_derivative, noise);
+    // d/dh (a h^p + b h^-q) = 0 gives h^(p+q) = q b / (p a)
+    let h_max = MAX_STEP * scale;
+    let h = if a > 0.0 {
+        ((q as f64 * b) / (p as f64 * a))
+            .powf(1.0 / (p + q) as f64)
+            .min(h_max)
+    } else {
+        h_max
+    };
+
+    let truncation_error = a * h.powi(p);
+    let round_off_error = b / h.powi(q);
+    StepSize {
+        h,
+        truncation_error,
+        round_off_error,
+        total_error: truncation_error + round_off_error,
+        noise,
+        higher_derivative,
+    }
+}
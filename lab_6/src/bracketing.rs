@@ -0,0 +1,343 @@
+use crate::root::{check_bracket, Iterate, Root, RootError, Tolerance};
+
+pub fn bisect_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let (mut xl, mut xu) = (xl, xu);
+    let (mut fl, fu) = check_bracket(&f, xl, xu)?;
+    if fl == 0.0 {
+        return Ok(bracket_root(xl, fl, xu - xl, Vec::new()));
+    }
+    if fu == 0.0 {
+        return Ok(bracket_root(xu, fu, xu - xl, Vec::new()));
+    }
+
+    let mut history = Vec::new();
+    for _ in 0..tol.max_iter {
+        let xr = xl + (xu - xl) / 2.0;
+        let fr = f(xr);
+        if !fr.is_finite() {
+            return Err(RootError::NonFinite { x: xr });
+        }
+
+        if fl * fr < 0.0 {
+            xu = xr;
+        } else {
+            xl = xr;
+            fl = fr;
+        }
+
+        history.push(Iterate { x: xr, fx: fr });
+        if fr == 0.0 || tol.f_converged(fr) || tol.x_converged(xu - xl, xr) {
+            return Ok(bracket_root(xr, fr, xu - xl, history));
+        }
+    }
+    let last = history.last().copied().unwrap_or(Iterate { x: xl, fx: fl });
+    Err(RootError::MaxIterations(bracket_root(
+        last.x,
+        last.fx,
+        xu - xl,
+        history,
+    )))
+}
+
+pub(crate) fn bracket_root(x: f64, residual: f64, width: f64, history: Vec<Iterate>) -> Root {
+    Root {
+        x,
+        residual,
+        iterations: history.len(),
+        bracket_width: Some(width),
+        history,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FalsePosition {
+    // Plain regula falsi
+    Plain,
+    // Halves the value kept at an end point that was retained twice in a row
+    Illinois,
+    // Scales the retained value by 1 - f(c)/f(b), falling back to Illinois
+    AndersonBjorck,
+}
+
+pub fn false_position_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    variant: FalsePosition,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let (mut fa, mut fb) = check_bracket(&f, xl, xu)?;
+    let (mut a, mut b) = (xl, xu);
+    if fa == 0.0 {
+        return Ok(bracket_root(a, fa, b - a, Vec::new()));
+    }
+    if fb == 0.0 {
+        return Ok(bracket_root(b, fb, b - a, Vec::new()));
+    }
+
+    let mut history: Vec<Iterate> = Vec::new();
+    for _ in 0..tol.max_iter {
+        let c = b - fb * (b - a) / (fb - fa);
+        let fc = f(c);
+        if !fc.is_finite() {
+            return Err(RootError::NonFinite { x: c });
+        }
+
+        if fc * fb < 0.0 {
+            a = b;
+            fa = fb;
+        } else {
+            // `a` is retained for the second time, shrink its function value
+            fa *= match variant {
+                FalsePosition::Plain => 1.0,
+                FalsePosition::Illinois => 0.5,
+                FalsePosition::AndersonBjorck => {
+                    let m = 1.0 - fc / fb;
+                    if m > 0.0 {
+                        m
+                    } else {
+                        0.5
+                    }
+                }
+            };
+        }
+        b = c;
+        fb = fc;
+
+        let step = history.last().map_or(b - a, |previous| c - previous.x);
+        history.push(Iterate { x: c, fx: fc });
+        if fc == 0.0 || tol.f_converged(fc) || tol.x_converged(step, c) || tol.x_converged(b - a, c)
+        {
+            return Ok(bracket_root(c, fc, (b - a).abs(), history));
+        }
+    }
+    let last = history.last().copied().unwrap_or(Iterate { x: b, fx: fb });
+    Err(RootError::MaxIterations(bracket_root(
+        last.x,
+        last.fx,
+        (b - a).abs(),
+        history,
+    )))
+}
+
+pub fn regula_falsi_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    false_position_method(f, xl, xu, FalsePosition::Plain, tol)
+}
+
+pub fn illinois_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    false_position_method(f, xl, xu, FalsePosition::Illinois, tol)
+}
+
+pub fn anderson_bjorck_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    false_position_method(f, xl, xu, FalsePosition::AndersonBjorck, tol)
+}
+
+// Brent's method: inverse quadratic interpolation or secant steps, falling
+// back to bisection whenever they would not shrink the bracket fast enough
+pub fn brent_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let (mut fa, mut fb) = check_bracket(&f, xl, xu)?;
+    let (mut a, mut b) = (xl, xu);
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    let mut history = Vec::new();
+    let mut widths = Vec::new();
+
+    for _ in 0..=tol.max_iter {
+        if fb * fc > 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        // Keep b as the best approximation
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol1 = 2.0 * f64::EPSILON * b.abs() + 0.5 * (tol.x_abs + tol.x_rel * b.abs());
+        let xm = 0.5 * (c - b);
+        if fb == 0.0 || tol.f_converged(fb) || xm.abs() <= tol1 {
+            return Ok(bracket_root(b, fb, (c - b).abs(), history));
+        }
+        if history.len() == tol.max_iter {
+            break;
+        }
+
+        // Interpolation can creep towards a multiple root from one side while c
+        // stays put. Bisecting when the bracket has not halved in two steps keeps
+        // the method within a small multiple of the iterations of bisection.
+        let width = (c - b).abs();
+        let stalled = widths.len() >= 2 && width > 0.5 * widths[widths.len() - 2];
+        widths.push(width);
+
+        if !stalled && e.abs() >= tol1 && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                // Secant step
+                (2.0 * xm * s, 1.0 - s)
+            } else {
+                // Inverse quadratic interpolation
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * xm * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min1 = 3.0 * xm * q - (tol1 * q).abs();
+            let min2 = (e * q).abs();
+            if 2.0 * p < min1.min(min2) {
+                e = d;
+                d = p / q;
+            } else {
+                d = xm;
+                e = d;
+            }
+        } else {
+            d = xm;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+        fb = f(b);
+        if !fb.is_finite() {
+            return Err(RootError::NonFinite { x: b });
+        }
+        history.push(Iterate { x: b, fx: fb });
+    }
+    Err(RootError::MaxIterations(bracket_root(
+        b,
+        fb,
+        (c - b).abs(),
+        history,
+    )))
+}
+
+// Ridders' method: fits an exponential through the end points and the
+// midpoint, which gives quadratic convergence while keeping the bracket
+pub fn ridders_method(
+    f: impl Fn(f64) -> f64,
+    xl: f64,
+    xu: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let (mut fa, mut fb) = check_bracket(&f, xl, xu)?;
+    let (mut a, mut b) = (xl, xu);
+    if fa == 0.0 {
+        return Ok(bracket_root(a, fa, b - a, Vec::new()));
+    }
+    if fb == 0.0 {
+        return Ok(bracket_root(b, fb, b - a, Vec::new()));
+    }
+
+    let mut history: Vec<Iterate> = Vec::new();
+    for _ in 0..tol.max_iter {
+        let xm = 0.5 * (a + b);
+        let fm = f(xm);
+        if !fm.is_finite() {
+            return Err(RootError::NonFinite { x: xm });
+        }
+        let s = (fm * fm - fa * fb).sqrt();
+        if s == 0.0 {
+            history.push(Iterate { x: xm, fx: fm });
+            return Ok(bracket_root(xm, fm, b - a, history));
+        }
+
+        let sign = if fa > fb { 1.0 } else { -1.0 };
+        let x = xm + (xm - a) * sign * fm / s;
+        let fx = f(x);
+        if !fx.is_finite() {
+            return Err(RootError::NonFinite { x });
+        }
+        history.push(Iterate { x, fx });
+
+        if fm * fx < 0.0 {
+            a = xm;
+            fa = fm;
+            b = x;
+            fb = fx;
+        } else if fa * fx < 0.0 {
+            b = x;
+            fb = fx;
+        } else {
+            a = x;
+            fa = fx;
+        }
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+
+        // Successive iterates can creep inside a wide bracket, so only its width counts
+        if fx == 0.0 || tol.f_converged(fx) || tol.x_converged(b - a, x) {
+            return Ok(bracket_root(x, fx, b - a, history));
+        }
+    }
+    let last = history.last().copied().unwrap_or(Iterate { x: b, fx: fb });
+    Err(RootError::MaxIterations(bracket_root(
+        last.x,
+        last.fx,
+        b - a,
+        history,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ridders_stops_on_the_bracket_width() {
+        let tol = Tolerance::new(1e-10, 0.0, 0.0, 100);
+        let root = ridders_method(|x| (x - 0.3).powi(9), 0.0, 1.0, &tol).unwrap();
+        assert!((root.x - 0.3).abs() <= 1e-10, "x = {}", root.x);
+        assert!(root.bracket_width.unwrap() <= 1e-10);
+    }
+
+    #[test]
+    fn brent_is_not_much_slower_than_bisection() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 100);
+        let cube = |x: f64| x.powi(3);
+        let brent = brent_method(cube, -1.0, 2.0, &tol).unwrap();
+        let bisection = bisect_method(cube, -1.0, 2.0, &tol).unwrap();
+        assert!(brent.x.abs() <= 1e-12, "x = {}", brent.x);
+        assert!(brent.iterations <= 3 * bisection.iterations);
+    }
+}
@@ -0,0 +1,2 @@
+// The complex type is shared with the complex-step derivative of lab_5
+pub use lab_5::complex_step::Complex;
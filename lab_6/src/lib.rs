@@ -0,0 +1,7 @@
+pub mod bracketing;
+pub mod complex;
+pub mod nonlinear;
+pub mod open_methods;
+pub mod polynomial;
+pub mod root;
+pub mod search;
@@ -1,12 +1,25 @@
 use plotly::common::Mode;
 use plotly::layout::Layout;
 use plotly::{Plot, Scatter};
-use roots::{find_root_brent, SimpleConvergency};
 
-fn graphical_method(f: fn(f64)->f64) {
+use lab_5::dual::{self, Scalar};
+use lab_6::bracketing::{
+    anderson_bjorck_method, bisect_method, brent_method, illinois_method, regula_falsi_method,
+    ridders_method,
+};
+use lab_6::nonlinear::{broyden_method, newton_system, NonlinearError, SystemRoot};
+use lab_6::open_methods::{
+    aitken_method, fixed_point_method, newton_method, secant_method, steffensen_method,
+};
+use lab_6::polynomial::Polynomial;
+use lab_6::root::{Root, RootError, Tolerance};
+use lab_6::search::{find_all_roots, BracketSolver, ScanOptions};
+
+fn graphical_method(f: fn(f64) -> f64) {
     let x_values = 0..=10;
     let y_values = x_values.clone().map(|x| f(x as f64));
-    let trace = Scatter::new(x_values.clone().collect(), y_values.collect()).mode(Mode::LinesMarkers);
+    let trace =
+        Scatter::new(x_values.clone().collect(), y_values.collect()).mode(Mode::LinesMarkers);
     let layout = Layout::new();
     let mut plot = Plot::new();
     plot.add_trace(trace);
@@ -14,88 +27,239 @@ fn graphical_method(f: fn(f64)->f64) {
     plot.show_image(plotly::ImageFormat::JPEG, 1000, 800);
 }
 
-fn bisect_method(f: fn(f64) -> f64, xl: f64, xu: f64, xr_old_option: Option<f64>) {
-    let xr_old = match xr_old_option {
-        Some(value) => value,
-        None => (xl + xu) / 2.0 + 0.1
-    };
-
-    if f(xl) * f(xu) > 0.0 {
-        println!("The function has the same sign at the end points");
-        return;
-    } else if f(xl) * f(xu) < 0.0 {
-        let xr = (xl + xu) / 2.0;
-        let error = (xr - xr_old).abs() / xr;
-        if -error < f(xl)*f(xr) && f(xr)*f(xr) < error {
-            println!("Result for Bisection is: {}", xr);
-            println!("Error: {:e}", f(xr));
-            return;
-        } else if f(xl) * f(xr) < 0.0 {
-            return bisect_method(f, xl, xr, Some(xr));
-        } else if f(xr) * f(xu) < 0.0 {
-            return bisect_method(f, xr, xu, Some(xr));
+fn compare_methods(f: fn(f64) -> f64, xl: f64, xu: f64, x0: f64, tol: &Tolerance) {
+    let results: Vec<(&str, Result<Root, RootError>)> = vec![
+        ("Bisection", bisect_method(f, xl, xu, tol)),
+        ("Regula falsi", regula_falsi_method(f, xl, xu, tol)),
+        ("Illinois", illinois_method(f, xl, xu, tol)),
+        ("Anderson-Bjorck", anderson_bjorck_method(f, xl, xu, tol)),
+        ("Brent", brent_method(f, xl, xu, tol)),
+        ("Ridders", ridders_method(f, xl, xu, tol)),
+        ("Secant", secant_method(f, xl, x0, tol)),
+        ("Newton", newton_method(f, None, x0, tol)),
+    ];
+    println!(
+        "{:<16} {:>20} {:>12} {:>12}",
+        "Method", "Root", "Iterations", "f(root)"
+    );
+    for (name, result) in results {
+        match result {
+            Ok(root) => println!(
+                "{:<16} {:>20.15} {:>12} {:>12.3e}",
+                name, root.x, root.iterations, root.residual
+            ),
+            Err(e) => println!("{:<16} failed: {}", name, e),
         }
-    } else if f(xl) == 0.0 {
-        println!("The root is at xl");
-        return;
-    } else if f(xu) == 0.0 {
-        println!("The root is at xu");
-        return;
     }
 }
 
+fn all_roots(name: &str, f: impl Fn(f64) -> f64, a: f64, b: f64, tol: &Tolerance) {
+    match find_all_roots(f, a, b, BracketSolver::Brent, &ScanOptions::default(), tol) {
+        Ok(search) => {
+            println!("Roots of {} in [{}, {}]:", name, a, b);
+            search.print();
+        }
+        Err(e) => println!("Root search failed: {}", e),
+    }
+}
 
-fn newton_method(f: fn(f64) -> f64, x: f64, tol: f64, max_iter: usize) {
-    let mut x0 = x;
-    for _ in 0..max_iter {
-        let dfx = derivative(f, x0);
-        if dfx == 0.0 {
-            println!("The derivative is zero");
-            return;
+fn polynomial_roots(p: &Polynomial, tol: &Tolerance) {
+    println!("Roots of p(x) = {}", p);
+    match p.roots_aberth(tol) {
+        Ok(roots) => {
+            for root in roots {
+                println!(
+                    "  Aberth: {} (multiplicity {})",
+                    root.value, root.multiplicity
+                );
+            }
         }
-        let x1 = x0 - f(x0) / dfx;
-        if (x1 - x0).abs() < tol*x1.abs() {
-            println!("Result for Newotn Mehod is: {}", x1);
-            println!("Error: {:e}", f(x1));
-            return;
+        Err(e) => println!("  Aberth failed: {}", e),
+    }
+    match p.roots_bairstow(tol) {
+        Ok(roots) => {
+            for root in roots {
+                println!("  Bairstow: {}", root);
+            }
         }
-        x0 = x1;
+        Err(e) => println!("  Bairstow failed: {}", e),
     }
-    println!("The method did not converge");
 }
 
+// Derivative of the Lennard-Jones potential from lab_13, rm = 1, epsilon = 0.1
+fn lennard_jones_derivative(r: f64) -> f64 {
+    let rm = 1.0;
+    let epsilon = 0.1;
+    12.0 * epsilon / r * ((rm / r).powi(6) - (rm / r).powi(12))
+}
 
-fn derivative(f: fn(f64) -> f64, x: f64) -> f64 {
-    let h = 10e-7;
-    (f(x + h/2.0) - f(x - h/2.0)) / h
+// Gradient of the potential energy of three particles at (0, 0), (a, 0) and (b, c).
+// Fixing the first particle and the direction to the second removes the
+// translations and the rotation, so the equilibrium is an isolated root.
+fn three_particle_gradient(q: &[f64]) -> Vec<f64> {
+    let (a, b, c) = (q[0], q[1], q[2]);
+    let r12 = a.abs();
+    let r13 = b.hypot(c);
+    let r23 = (b - a).hypot(c);
+    let (d12, d13, d23) = (
+        lennard_jones_derivative(r12),
+        lennard_jones_derivative(r13),
+        lennard_jones_derivative(r23),
+    );
+    vec![
+        d12 * a / r12 + d23 * (a - b) / r23,
+        d13 * b / r13 + d23 * (b - a) / r23,
+        d13 * c / r13 + d23 * c / r23,
+    ]
 }
 
-fn library_method(a:f64, b:f64, f: fn(f64) -> f64) {
-    let result = find_root_brent(a, b, &f, &mut SimpleConvergency { eps: 1e-7, max_iter: 100 });
+fn print_system_root(name: &str, result: Result<SystemRoot, NonlinearError>) {
     match result {
-        Ok(root) => println!("Result for library method is: {} \n Error {:e}", root, f(root)),
-        Err(e) => println!("Error: {:?}", e),
+        Ok(root) => println!(
+            "{}: x = {:?}, iterations = {}, ||F|| history = {:?}",
+            name, root.x, root.iterations, root.history
+        ),
+        Err(e) => println!("{} failed: {}", name, e),
     }
 }
 
-fn example_funciton(h: f64) -> f64 {
+fn nonlinear_systems(tol: &Tolerance) {
+    let f = |x: &[f64]| vec![x[0].powi(2) + x[1].powi(2) - 4.0, x[0].exp() + x[1] - 1.0];
+    let jacobian = |x: &[f64]| vec![vec![2.0 * x[0], 2.0 * x[1]], vec![x[0].exp(), 1.0]];
+    let x0 = [1.0, -1.0];
+    print_system_root(
+        "Newton (analytic Jacobian)",
+        newton_system(f, Some(&jacobian), &x0, tol),
+    );
+    print_system_root(
+        "Newton (finite differences)",
+        newton_system(f, None, &x0, tol),
+    );
+    print_system_root("Broyden", broyden_method(f, &x0, tol));
+
+    // Equilibrium of three Lennard-Jones particles is an equilateral triangle with side rm
+    let q0 = [1.1, 0.45, 0.9];
+    print_system_root(
+        "Lennard-Jones, Newton",
+        newton_system(three_particle_gradient, None, &q0, tol),
+    );
+    print_system_root(
+        "Lennard-Jones, Broyden",
+        broyden_method(three_particle_gradient, &q0, tol),
+    );
+}
+
+// example_funciton rewritten as h = g(h): sqrt(2gh) tanh(sqrt(2gh) t / 2l) = vt
+// gives h = (vt / tanh(sqrt(2gh) t / 2l))^2 / 2g
+fn example_fixed_point(h: f64) -> f64 {
     let g = 9.81;
     let l = 5.0;
     let t = 3.0;
     let vt = 4.0;
     let sqrt_op = (2.0 * g * h).sqrt();
-    let v_h = sqrt_op * tanh(sqrt_op / (2.0*l) * t) - vt; 
-    return v_h;
+    (vt / (sqrt_op / (2.0 * l) * t).tanh()).powi(2) / (2.0 * g)
+}
+
+fn fixed_point_methods(tol: &Tolerance) {
+    let results: Vec<(&str, Result<Root, RootError>)> = vec![
+        (
+            "Fixed point",
+            fixed_point_method(example_fixed_point, 2.0, tol),
+        ),
+        ("Aitken", aitken_method(example_fixed_point, 2.0, tol)),
+        (
+            "Steffensen",
+            steffensen_method(example_fixed_point, 2.0, tol),
+        ),
+    ];
+    for (name, result) in results {
+        match result {
+            Ok(root) => {
+                root.print(name);
+                let contraction = root.contraction_factors();
+                let order = root.observed_order();
+                println!("  Contraction factors: {:?}", contraction);
+                println!("  Observed order: {:?}", order);
+            }
+            Err(e) => println!("{} failed: {}", name, e),
+        }
+    }
 }
 
-fn tanh(x: f64) -> f64 {
-    (x.exp() - (-x).exp()) / (x.exp() + (-x).exp())
+fn example_funciton<T: Scalar>(h: T) -> T {
+    let g = T::from(9.81);
+    let l = T::from(5.0);
+    let t = T::from(3.0);
+    let vt = T::from(4.0);
+    let sqrt_op = (T::from(2.0) * g * h).sqrt();
+    sqrt_op * (sqrt_op / (T::from(2.0) * l) * t).tanh() - vt
 }
 
 fn main() {
     let function = example_funciton;
     graphical_method(function);
-    bisect_method(function, 0.0, 10.0, None);
-    newton_method(function, 2.0, 0.001, 100);
-    library_method(0.0, 10.0, function);
+    let tol = Tolerance::new(1e-10, 1e-10, 0.0, 100);
+    match bisect_method(function, 0.0, 10.0, &tol) {
+        Ok(root) => root.print("Bisection"),
+        Err(e) => println!("Bisection failed: {}", e),
+    }
+    let exact_derivative = |h| dual::derivative(example_funciton, h);
+    for (name, df) in [
+        ("Newton Method", None),
+        (
+            "Newton Method (dual numbers)",
+            Some(&exact_derivative as &dyn Fn(f64) -> f64),
+        ),
+    ] {
+        match newton_method(function, df, 2.0, &tol) {
+            Ok(root) => {
+                root.print(name);
+                for (k, it) in root.history.iter().enumerate() {
+                    println!("  x{} = {}, f(x{}) = {:e}", k, it.x, k, it.fx);
+                }
+                println!("  Observed order: {:?}", root.observed_order());
+            }
+            Err(e) => println!("{} failed: {}", name, e),
+        }
+    }
+    match brent_method(function, 0.0, 10.0, &tol) {
+        Ok(root) => {
+            root.print("Brent");
+            for (k, it) in root.history.iter().enumerate() {
+                println!("  x{} = {}, f(x{}) = {:e}", k + 1, it.x, k + 1, it.fx);
+            }
+        }
+        Err(e) => println!("Brent failed: {}", e),
+    }
+    compare_methods(function, 0.0, 10.0, 2.0, &tol);
+    all_roots("v(h) - vt", function, 0.0, 10.0, &tol);
+    all_roots("sin(x)", f64::sin, 0.0, 10.0, &tol);
+    all_roots(
+        "(x - 2.0537)^2 (x - 5)",
+        |x| (x - 2.0537).powi(2) * (x - 5.0),
+        0.0,
+        10.0,
+        &tol,
+    );
+    all_roots(
+        "(x - 3.01)(x - 3.03)",
+        |x| (x - 3.01) * (x - 3.03),
+        0.0,
+        10.0,
+        &tol,
+    );
+
+    // lab_4::polinomial and lab_5::function_2
+    polynomial_roots(
+        &Polynomial::from_highest(&[-0.1, -0.15, -0.5, -0.25, 1.2]),
+        &tol,
+    );
+    polynomial_roots(&Polynomial::from_highest(&[1.0, -2.0, 3.0, -1.0]), &tol);
+    nonlinear_systems(&tol);
+    fixed_point_methods(&tol);
+
+    let multiple = Polynomial::from_roots(&[1.0, 1.0, 1.0, -2.0]);
+    polynomial_roots(&multiple, &tol);
+    let (quotient, remainder) = multiple.deflate(-2.0);
+    println!("p(x) / (x + 2) = {}, remainder {}", quotient, remainder);
 }
@@ -0,0 +1,303 @@
+use std::fmt;
+
+use lab_8::equation::{EquationSystem, Pivoting, SystemError};
+
+use crate::root::Tolerance;
+
+// Smallest fraction of the full step tried by the line search
+const MIN_STEP_FRACTION: f64 = 1.0 / 1024.0;
+// Sufficient decrease required by the line search (Armijo condition)
+const ARMIJO: f64 = 1e-4;
+
+pub type Jacobian<'a> = &'a dyn Fn(&[f64]) -> Vec<Vec<f64>>;
+// A point x together with F(x)
+type Evaluated = (Vec<f64>, Vec<f64>);
+
+#[derive(Debug, Clone)]
+pub struct SystemRoot {
+    pub x: Vec<f64>,
+    // F(x) at the returned solution
+    pub residual: Vec<f64>,
+    pub iterations: usize,
+    // ||F(x)||_inf after every iteration, starting with the initial guess
+    pub history: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum NonlinearError {
+    DimensionMismatch { expected: usize, found: usize },
+    NonFinite { x: Vec<f64> },
+    Linear(SystemError),
+    LineSearchFailed(SystemRoot),
+    MaxIterations(SystemRoot),
+}
+
+impl fmt::Display for NonlinearError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NonlinearError::DimensionMismatch { expected, found } => write!(
+                f,
+                "expected {} equations or unknowns, got {}",
+                expected, found
+            ),
+            NonlinearError::NonFinite { x } => write!(f, "F is not finite at x = {:?}", x),
+            NonlinearError::Linear(e) => write!(f, "the linear step failed: {}", e),
+            NonlinearError::LineSearchFailed(root) => write!(
+                f,
+                "the line search could not decrease ||F|| after {} iterations (x = {:?})",
+                root.iterations, root.x
+            ),
+            NonlinearError::MaxIterations(root) => write!(
+                f,
+                "the method did not converge in {} iterations (x = {:?})",
+                root.iterations, root.x
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NonlinearError {}
+
+impl From<SystemError> for NonlinearError {
+    fn from(e: SystemError) -> NonlinearError {
+        NonlinearError::Linear(e)
+    }
+}
+
+// NaN is propagated so that it can never pass for a small norm
+fn norm_inf(v: &[f64]) -> f64 {
+    v.iter().fold(0.0, |m: f64, a| {
+        if m.is_nan() || a.is_nan() {
+            f64::NAN
+        } else {
+            m.max(a.abs())
+        }
+    })
+}
+
+fn evaluate(f: &impl Fn(&[f64]) -> Vec<f64>, x: &[f64]) -> Result<Vec<f64>, NonlinearError> {
+    let fx = f(x);
+    if fx.len() != x.len() {
+        return Err(NonlinearError::DimensionMismatch {
+            expected: x.len(),
+            found: fx.len(),
+        });
+    }
+    if fx.iter().any(|v| !v.is_finite()) {
+        return Err(NonlinearError::NonFinite { x: x.to_vec() });
+    }
+    Ok(fx)
+}
+
+// Forward differences, column j is (F(x + h e_j) - F(x)) / h
+pub fn finite_difference_jacobian(
+    f: &impl Fn(&[f64]) -> Vec<f64>,
+    x: &[f64],
+    fx: &[f64],
+) -> Vec<Vec<f64>> {
+    let n = x.len();
+    let mut jacobian = vec![vec![0.0; n]; fx.len()];
+    let mut xh = x.to_vec();
+    for j in 0..n {
+        let h = f64::EPSILON.sqrt() * x[j].abs().max(1.0);
+        xh[j] = x[j] + h;
+        let fxh = f(&xh);
+        xh[j] = x[j];
+        for (row, (a, b)) in jacobian.iter_mut().zip(fxh.iter().zip(fx)) {
+            row[j] = (a - b) / h;
+        }
+    }
+    jacobian
+}
+
+// Solves J p = -F(x) with Gaussian elimination from lab_8
+fn newton_step(jacobian: Vec<Vec<f64>>, fx: &[f64]) -> Result<Vec<f64>, NonlinearError> {
+    let system = EquationSystem::from_matrix(jacobian, fx.iter().map(|v| -v).collect())?;
+    Ok(system.solve_gauss_el(Pivoting::Partial)?)
+}
+
+// Backtracks along p until ||F|| decreases enough, returns the accepted x and F(x).
+// Candidates where F is not finite are rejected like any other that does not decrease ||F||.
+fn line_search(
+    f: &impl Fn(&[f64]) -> Vec<f64>,
+    x: &[f64],
+    fx_norm: f64,
+    p: &[f64],
+) -> Result<Option<Evaluated>, NonlinearError> {
+    let mut lambda = 1.0;
+    while lambda >= MIN_STEP_FRACTION {
+        let candidate = x
+            .iter()
+            .zip(p)
+            .map(|(xi, pi)| xi + lambda * pi)
+            .collect::<Vec<f64>>();
+        match evaluate(f, &candidate) {
+            Ok(fc) if norm_inf(&fc) <= (1.0 - ARMIJO * lambda) * fx_norm => {
+                return Ok(Some((candidate, fc)));
+            }
+            Ok(_) | Err(NonlinearError::NonFinite { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        lambda /= 2.0;
+    }
+    Ok(None)
+}
+
+// Near the solution rounding keeps ||F|| from decreasing, which is not a failure
+// when F is already small or the Newton step p is negligible
+fn stalled_at_solution(tol: &Tolerance, x: &[f64], fx: &[f64], p: &[f64]) -> bool {
+    tol.f_converged(norm_inf(fx)) || tol.x_converged(norm_inf(p), norm_inf(x))
+}
+
+fn converged(tol: &Tolerance, x: &[f64], previous: &[f64], fx: &[f64]) -> bool {
+    let step = x
+        .iter()
+        .zip(previous)
+        .map(|(a, b)| a - b)
+        .collect::<Vec<f64>>();
+    tol.f_converged(norm_inf(fx)) || tol.x_converged(norm_inf(&step), norm_inf(x))
+}
+
+fn system_root(x: Vec<f64>, residual: Vec<f64>, history: Vec<f64>) -> SystemRoot {
+    SystemRoot {
+        x,
+        residual,
+        iterations: history.len() - 1,
+        history,
+    }
+}
+
+// Newton's method for F(x) = 0 with a backtracking line search. Without an
+// analytic Jacobian a forward-difference approximation is used.
+pub fn newton_system(
+    f: impl Fn(&[f64]) -> Vec<f64>,
+    jacobian: Option<Jacobian>,
+    x0: &[f64],
+    tol: &Tolerance,
+) -> Result<SystemRoot, NonlinearError> {
+    let mut x = x0.to_vec();
+    let mut fx = evaluate(&f, &x)?;
+    let mut history = vec![norm_inf(&fx)];
+    if norm_inf(&fx) == 0.0 {
+        return Ok(system_root(x, fx, history));
+    }
+
+    for _ in 0..tol.max_iter {
+        let j = match jacobian {
+            Some(jacobian) => jacobian(&x),
+            None => finite_difference_jacobian(&f, &x, &fx),
+        };
+        let p = newton_step(j, &fx)?;
+        let Some((x_new, fx_new)) = line_search(&f, &x, norm_inf(&fx), &p)? else {
+            if stalled_at_solution(tol, &x, &fx, &p) {
+                return Ok(system_root(x, fx, history));
+            }
+            return Err(NonlinearError::LineSearchFailed(system_root(
+                x, fx, history,
+            )));
+        };
+        history.push(norm_inf(&fx_new));
+        let done = converged(tol, &x_new, &x, &fx_new);
+        x = x_new;
+        fx = fx_new;
+        if done {
+            return Ok(system_root(x, fx, history));
+        }
+    }
+    Err(NonlinearError::MaxIterations(system_root(x, fx, history)))
+}
+
+// Broyden's quasi-Newton method: the Jacobian is approximated by finite
+// differences once and then corrected with rank-one updates,
+// B += (dF - B dx) dx^T / (dx^T dx), so F is evaluated only once per iteration
+pub fn broyden_method(
+    f: impl Fn(&[f64]) -> Vec<f64>,
+    x0: &[f64],
+    tol: &Tolerance,
+) -> Result<SystemRoot, NonlinearError> {
+    let mut x = x0.to_vec();
+    let mut fx = evaluate(&f, &x)?;
+    let mut history = vec![norm_inf(&fx)];
+    if norm_inf(&fx) == 0.0 {
+        return Ok(system_root(x, fx, history));
+    }
+    let mut b = finite_difference_jacobian(&f, &x, &fx);
+    let mut refreshed = true;
+
+    for _ in 0..tol.max_iter {
+        let p = newton_step(b.clone(), &fx)?;
+        let Some((x_new, fx_new)) = line_search(&f, &x, norm_inf(&fx), &p)? else {
+            if stalled_at_solution(tol, &x, &fx, &p) {
+                return Ok(system_root(x, fx, history));
+            }
+            if refreshed {
+                return Err(NonlinearError::LineSearchFailed(system_root(
+                    x, fx, history,
+                )));
+            }
+            // The approximation went stale, start again from a fresh Jacobian
+            b = finite_difference_jacobian(&f, &x, &fx);
+            refreshed = true;
+            continue;
+        };
+        refreshed = false;
+
+        let dx = x_new
+            .iter()
+            .zip(&x)
+            .map(|(a, b)| a - b)
+            .collect::<Vec<f64>>();
+        let df = fx_new
+            .iter()
+            .zip(&fx)
+            .map(|(a, b)| a - b)
+            .collect::<Vec<f64>>();
+        let dx_dot: f64 = dx.iter().map(|v| v * v).sum();
+        if dx_dot > 0.0 {
+            for (row, dfi) in b.iter_mut().zip(&df) {
+                let b_dx: f64 = row.iter().zip(&dx).map(|(a, d)| a * d).sum();
+                let factor = (dfi - b_dx) / dx_dot;
+                for (a, d) in row.iter_mut().zip(&dx) {
+                    *a += factor * d;
+                }
+            }
+        }
+
+        history.push(norm_inf(&fx_new));
+        let done = converged(tol, &x_new, &x, &fx_new);
+        x = x_new;
+        fx = fx_new;
+        if done {
+            return Ok(system_root(x, fx, history));
+        }
+    }
+    Err(NonlinearError::MaxIterations(system_root(x, fx, history)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_search_rejects_points_outside_the_domain() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 100);
+        let f = |x: &[f64]| vec![x[0].ln() - 0.3];
+        let root = newton_system(f, None, &[5.0], &tol).unwrap();
+        assert!(
+            (root.x[0] - 0.3_f64.exp()).abs() <= 1e-10,
+            "x = {:?}",
+            root.x
+        );
+    }
+
+    #[test]
+    fn converged_point_is_not_a_line_search_failure() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 100);
+        let f = |x: &[f64]| vec![x[0].exp() - 3.0, x[0] + x[1] - 0.1, x[2] * x[2] - 0.7];
+        let root = newton_system(f, None, &[1.0, 1.0, 1.0], &tol).unwrap();
+        let expected = [3.0_f64.ln(), 0.1 - 3.0_f64.ln(), 0.7_f64.sqrt()];
+        for (x, e) in root.x.iter().zip(expected) {
+            assert!((x - e).abs() <= 1e-10, "x = {:?}", root.x);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::root::{Iterate, Root, RootError, Tolerance};
+
+// Halvings of the Newton step tried before giving up on decreasing |f|
+const MAX_DAMPING: usize = 10;
+// Consecutive iterations without a decrease of |f| before reporting divergence
+const MAX_NON_DECREASING: usize = 5;
+const MAX_CYCLE_PERIOD: usize = 4;
+
+pub fn derivative(f: &impl Fn(f64) -> f64, x: f64) -> f64 {
+    let h = f64::EPSILON.cbrt() * x.abs().max(1.0);
+    (f(x + h / 2.0) - f(x - h / 2.0)) / h
+}
+
+// Newton's method with backtracking: the step is halved while it does not
+// decrease |f|. Without an analytic derivative a central difference is used.
+pub fn newton_method(
+    f: impl Fn(f64) -> f64,
+    df: Option<&dyn Fn(f64) -> f64>,
+    x: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let mut x0 = x;
+    let mut fx0 = f(x0);
+    if !fx0.is_finite() {
+        return Err(RootError::NonFinite { x: x0 });
+    }
+    let mut history = vec![Iterate { x: x0, fx: fx0 }];
+    if fx0 == 0.0 {
+        return Ok(open_root(history));
+    }
+    let mut non_decreasing = 0;
+
+    for _ in 0..tol.max_iter {
+        let dfx = match df {
+            Some(df) => df(x0),
+            None => derivative(&f, x0),
+        };
+        if dfx == 0.0 || !dfx.is_finite() {
+            return Err(RootError::ZeroDerivative { x: x0 });
+        }
+
+        let step = -fx0 / dfx;
+        let mut lambda = 1.0;
+        let mut x1 = x0 + step;
+        let mut fx1 = f(x1);
+        for _ in 0..MAX_DAMPING {
+            if fx1.is_finite() && fx1.abs() < fx0.abs() {
+                break;
+            }
+            lambda /= 2.0;
+            x1 = x0 + lambda * step;
+            fx1 = f(x1);
+        }
+        if !fx1.is_finite() {
+            return Err(RootError::NonFinite { x: x1 });
+        }
+
+        if fx1.abs() >= fx0.abs() {
+            non_decreasing += 1;
+        } else {
+            non_decreasing = 0;
+        }
+        history.push(Iterate { x: x1, fx: fx1 });
+
+        if fx1 == 0.0 || tol.f_converged(fx1) || tol.x_converged(x1 - x0, x1) {
+            return Ok(open_root(history));
+        }
+        if let Some(period) = cycle_period(&history, tol) {
+            return Err(RootError::Cycling { x: x1, period });
+        }
+        if non_decreasing >= MAX_NON_DECREASING {
+            return Err(RootError::Diverged(open_root(history)));
+        }
+        x0 = x1;
+        fx0 = fx1;
+    }
+    Err(RootError::MaxIterations(open_root(history)))
+}
+
+pub fn secant_method(
+    f: impl Fn(f64) -> f64,
+    x0: f64,
+    x1: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let (mut x0, mut x1) = (x0, x1);
+    let (mut fx0, mut fx1) = (f(x0), f(x1));
+    for (x, fx) in [(x0, fx0), (x1, fx1)] {
+        if !fx.is_finite() {
+            return Err(RootError::NonFinite { x });
+        }
+    }
+    let mut history = vec![Iterate { x: x0, fx: fx0 }, Iterate { x: x1, fx: fx1 }];
+    if fx1 == 0.0 {
+        return Ok(open_root(history));
+    }
+
+    for _ in 0..tol.max_iter {
+        if fx1 == fx0 {
+            return Err(RootError::ZeroDerivative { x: x1 });
+        }
+        let x2 = x1 - fx1 * (x1 - x0) / (fx1 - fx0);
+        let fx2 = f(x2);
+        if !fx2.is_finite() {
+            return Err(RootError::NonFinite { x: x2 });
+        }
+        history.push(Iterate { x: x2, fx: fx2 });
+
+        if fx2 == 0.0 || tol.f_converged(fx2) || tol.x_converged(x2 - x1, x2) {
+            return Ok(open_root(history));
+        }
+        if let Some(period) = cycle_period(&history, tol) {
+            return Err(RootError::Cycling { x: x2, period });
+        }
+        x0 = x1;
+        fx0 = fx1;
+        x1 = x2;
+        fx1 = fx2;
+    }
+    Err(RootError::MaxIterations(open_root(history)))
+}
+
+// Fixed-point iteration x = g(x). The contraction factor q is estimated from
+// successive steps; the iteration stops once the error bound q / (1 - q) |dx|
+// is within tolerance and is reported as diverged when q stays at or above 1.
+// The residual of every iterate is g(x) - x.
+pub fn fixed_point_method(
+    g: impl Fn(f64) -> f64,
+    x0: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let mut x = x0;
+    let mut gx = g(x);
+    if !gx.is_finite() {
+        return Err(RootError::NonFinite { x });
+    }
+    let mut history = vec![Iterate { x, fx: gx - x }];
+    let mut previous_step = f64::NAN;
+    let mut expanding = 0;
+
+    for _ in 0..tol.max_iter {
+        let x_new = gx;
+        let g_new = g(x_new);
+        if !g_new.is_finite() {
+            return Err(RootError::NonFinite { x: x_new });
+        }
+        let step = x_new - x;
+        history.push(Iterate {
+            x: x_new,
+            fx: g_new - x_new,
+        });
+
+        let q = step.abs() / previous_step.abs();
+        let error_bound = if q < 1.0 {
+            q / (1.0 - q) * step.abs()
+        } else {
+            step.abs()
+        };
+        if step == 0.0 || tol.f_converged(g_new - x_new) || tol.x_converged(error_bound, x_new) {
+            return Ok(open_root(history));
+        }
+        if q >= 1.0 {
+            expanding += 1;
+            if expanding >= MAX_NON_DECREASING {
+                return Err(RootError::Diverged(open_root(history)));
+            }
+        } else {
+            expanding = 0;
+        }
+        // An alternating sequence that converges also returns close to the
+        // iterate two steps back, so a cycle needs steps that are not shrinking
+        if q > 1.0 - f64::EPSILON.sqrt() {
+            if let Some(period) = cycle_period(&history, tol) {
+                return Err(RootError::Cycling { x: x_new, period });
+            }
+        }
+
+        previous_step = step;
+        x = x_new;
+        gx = g_new;
+    }
+    Err(RootError::MaxIterations(open_root(history)))
+}
+
+// Aitken's delta-squared extrapolation of x0, x1, x2
+fn aitken(x0: f64, x1: f64, x2: f64) -> Option<f64> {
+    let denominator = x2 - 2.0 * x1 + x0;
+    if denominator == 0.0 {
+        None
+    } else {
+        Some(x0 - (x1 - x0).powi(2) / denominator)
+    }
+}
+
+// Plain fixed-point iteration whose iterates are replaced by their Aitken
+// extrapolations; the history holds the accelerated sequence
+pub fn aitken_method(g: impl Fn(f64) -> f64, x0: f64, tol: &Tolerance) -> Result<Root, RootError> {
+    let mut x = [x0, g(x0), f64::NAN];
+    if !x[1].is_finite() {
+        return Err(RootError::NonFinite { x: x0 });
+    }
+    x[2] = g(x[1]);
+    if !x[2].is_finite() {
+        return Err(RootError::NonFinite { x: x[1] });
+    }
+    let mut history = vec![Iterate {
+        x: x0,
+        fx: x[1] - x0,
+    }];
+
+    for _ in 0..tol.max_iter {
+        let Some(accelerated) = aitken(x[0], x[1], x[2]) else {
+            // The plain sequence already settled
+            history.push(Iterate {
+                x: x[2],
+                fx: g(x[2]) - x[2],
+            });
+            return Ok(open_root(history));
+        };
+        let residual = g(accelerated) - accelerated;
+        let step = accelerated - history[history.len() - 1].x;
+        history.push(Iterate {
+            x: accelerated,
+            fx: residual,
+        });
+        if residual == 0.0 || tol.f_converged(residual) || tol.x_converged(step, accelerated) {
+            return Ok(open_root(history));
+        }
+
+        let next = g(x[2]);
+        if !next.is_finite() {
+            return Err(RootError::NonFinite { x: x[2] });
+        }
+        x = [x[1], x[2], next];
+    }
+    Err(RootError::MaxIterations(open_root(history)))
+}
+
+// Steffensen's method: every step restarts the iteration from the Aitken
+// extrapolation of x, g(x), g(g(x)), which gives quadratic convergence
+pub fn steffensen_method(
+    g: impl Fn(f64) -> f64,
+    x0: f64,
+    tol: &Tolerance,
+) -> Result<Root, RootError> {
+    let mut x = x0;
+    let mut history = vec![Iterate { x, fx: g(x) - x }];
+
+    for _ in 0..tol.max_iter {
+        let x1 = g(x);
+        let x2 = g(x1);
+        if !(x1.is_finite() && x2.is_finite()) {
+            return Err(RootError::NonFinite { x });
+        }
+        let x_new = aitken(x, x1, x2).unwrap_or(x2);
+        let residual = g(x_new) - x_new;
+        history.push(Iterate {
+            x: x_new,
+            fx: residual,
+        });
+        if residual == 0.0 || tol.f_converged(residual) || tol.x_converged(x_new - x, x_new) {
+            return Ok(open_root(history));
+        }
+        if let Some(period) = cycle_period(&history, tol) {
+            return Err(RootError::Cycling { x: x_new, period });
+        }
+        x = x_new;
+    }
+    Err(RootError::MaxIterations(open_root(history)))
+}
+
+// Period of a cycle if the last iterate returns to one of the few before it
+pub(crate) fn cycle_period(history: &[Iterate], tol: &Tolerance) -> Option<usize> {
+    let last = history.last()?.x;
+    (2..=MAX_CYCLE_PERIOD).find(|period| {
+        history.len() > *period
+            && tol.x_converged(last - history[history.len() - 1 - period].x, last)
+    })
+}
+
+pub(crate) fn open_root(history: Vec<Iterate>) -> Root {
+    let last = history[history.len() - 1];
+    Root {
+        x: last.x,
+        residual: last.fx,
+        iterations: history.len() - 1,
+        bracket_width: None,
+        history,
+    }
+}
@@ -0,0 +1,394 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::complex::Complex;
+use crate::root::{RootError, Tolerance};
+
+// Roots closer than this (relative to their magnitude) are merged into one
+// multiple root. A root of multiplicity m is only found to about eps^(1/m).
+const MULTIPLICITY_RADIUS: f64 = 1e-4;
+
+// p(x) = a0 + a1 x + ... + an x^n, coefficients are stored from a0 up
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    coefficients: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolynomialRoot {
+    pub value: Complex,
+    pub multiplicity: usize,
+}
+
+impl Polynomial {
+    // Trailing zero coefficients are dropped so that the degree is exact
+    pub fn new(coefficients: Vec<f64>) -> Polynomial {
+        let mut coefficients = coefficients;
+        while coefficients.len() > 1 && coefficients[coefficients.len() - 1] == 0.0 {
+            coefficients.pop();
+        }
+        if coefficients.is_empty() {
+            coefficients.push(0.0);
+        }
+        Polynomial { coefficients }
+    }
+
+    // Coefficients given from the highest power down, as the polynomial is usually written
+    pub fn from_highest(coefficients: &[f64]) -> Polynomial {
+        Polynomial::new(coefficients.iter().rev().copied().collect())
+    }
+
+    pub fn from_roots(roots: &[f64]) -> Polynomial {
+        roots.iter().fold(Polynomial::new(vec![1.0]), |p, r| {
+            p.multiply(&Polynomial::new(vec![-r, 1.0]))
+        })
+    }
+
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    pub fn evaluate(&self, x: f64) -> f64 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, a| acc * x + a)
+    }
+
+    pub fn evaluate_complex(&self, z: Complex) -> Complex {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Complex::from(0.0), |acc, a| acc * z + Complex::from(*a))
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        if self.degree() == 0 {
+            return Polynomial::new(vec![0.0]);
+        }
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, a)| i as f64 * a)
+                .collect(),
+        )
+    }
+
+    pub fn multiply(&self, other: &Polynomial) -> Polynomial {
+        let mut product = vec![0.0; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+        Polynomial::new(product)
+    }
+
+    // Synthetic division by (x - r), returns the quotient and the remainder p(r)
+    pub fn deflate(&self, r: f64) -> (Polynomial, f64) {
+        let n = self.degree();
+        if n == 0 {
+            return (Polynomial::new(vec![0.0]), self.coefficients[0]);
+        }
+        let mut quotient = vec![0.0; n];
+        let mut carry = self.coefficients[n];
+        for i in (0..n).rev() {
+            quotient[i] = carry;
+            carry = self.coefficients[i] + carry * r;
+        }
+        (Polynomial::new(quotient), carry)
+    }
+
+    // Division by (x^2 - r x - s), returns the quotient and the remainder b1 (x - r) + b0
+    pub fn deflate_quadratic(&self, r: f64, s: f64) -> (Polynomial, (f64, f64)) {
+        if self.degree() < 2 {
+            return (Polynomial::new(vec![0.0]), (0.0, 0.0));
+        }
+        let b = bairstow_recurrence(&self.coefficients, r, s);
+        (Polynomial::new(b[2..].to_vec()), (b[1], b[0]))
+    }
+
+    // Cauchy's bound, every root satisfies |z| <= 1 + max |a_i / a_n|
+    pub fn root_bound(&self) -> f64 {
+        let n = self.degree();
+        let leading = self.coefficients[n];
+        1.0 + self.coefficients[..n]
+            .iter()
+            .map(|a| (a / leading).abs())
+            .fold(0.0, f64::max)
+    }
+
+    // All complex roots with the Aberth-Ehrlich method: Newton steps for every
+    // root at once, each corrected by the repulsion of the others
+    pub fn roots_aberth(&self, tol: &Tolerance) -> Result<Vec<PolynomialRoot>, RootError> {
+        if self.degree() == 0 {
+            return Err(RootError::ConstantPolynomial);
+        }
+        // Roots at zero are taken out exactly before iterating
+        let zeros = self.coefficients.iter().take_while(|a| **a == 0.0).count();
+        let p = Polynomial::new(self.coefficients[zeros..].to_vec());
+        let dp = p.derivative();
+        let n = p.degree();
+
+        let radius = p.root_bound() / 2.0;
+        let mut z = (0..n)
+            .map(|k| Complex::from_polar(radius, 2.0 * PI * k as f64 / n as f64 + 0.4))
+            .collect::<Vec<Complex>>();
+
+        // Near a root of multiplicity m the corrections stall at about eps^(1/m),
+        // so a root is also done once p(z) is within the rounding error of Horner's
+        // scheme, 2n eps sum |a_i| |z|^i
+        let magnitudes = Polynomial::new(p.coefficients.iter().map(|a| a.abs()).collect());
+        let rounding = |z: Complex| 2.0 * n as f64 * f64::EPSILON * magnitudes.evaluate(z.abs());
+        let mut done = vec![false; n];
+        for _ in 0..tol.max_iter {
+            if done.iter().all(|d| *d) {
+                break;
+            }
+            for k in 0..n {
+                if done[k] {
+                    continue;
+                }
+                let pz = p.evaluate_complex(z[k]);
+                if pz.abs() <= rounding(z[k]) {
+                    done[k] = true;
+                    continue;
+                }
+                let ratio = pz / dp.evaluate_complex(z[k]);
+                let repulsion = (0..n)
+                    .filter(|j| *j != k)
+                    .fold(Complex::from(0.0), |sum, j| {
+                        sum + Complex::from(1.0) / (z[k] - z[j])
+                    });
+                let w = ratio / (Complex::from(1.0) - ratio * repulsion);
+                if !(w.re.is_finite() && w.im.is_finite()) {
+                    // No usable correction, this root has not converged yet
+                    continue;
+                }
+                z[k] = z[k] - w;
+                done[k] = tol.x_converged(w.abs(), z[k].abs());
+            }
+        }
+        if !done.iter().all(|d| *d) {
+            return Err(RootError::PolynomialNotConverged {
+                iterations: tol.max_iter,
+            });
+        }
+
+        // A disk of radius n |p / p'| around every approximation holds a root,
+        // around a multiple root these disks overlap
+        let mut radii = z
+            .iter()
+            .map(|zk| n as f64 * (p.evaluate_complex(*zk) / dp.evaluate_complex(*zk)).abs())
+            .map(|r| if r.is_finite() { r } else { 0.0 })
+            .collect::<Vec<f64>>();
+        z.extend(std::iter::repeat_n(Complex::from(0.0), zeros));
+        radii.extend(std::iter::repeat_n(0.0, zeros));
+        let mut roots = group_roots(z, &radii);
+        for root in roots.iter_mut().filter(|root| root.multiplicity > 1) {
+            root.value = self.polish_multiple(root.value, root.multiplicity, tol);
+        }
+        Ok(roots)
+    }
+
+    // A root of multiplicity m is a simple root of the (m - 1)-th derivative,
+    // where Newton's method converges quadratically again
+    fn polish_multiple(&self, z: Complex, multiplicity: usize, tol: &Tolerance) -> Complex {
+        let q = (1..multiplicity).fold(self.clone(), |q, _| q.derivative());
+        let dq = q.derivative();
+        let mut z = z;
+        for _ in 0..tol.max_iter {
+            let step = q.evaluate_complex(z) / dq.evaluate_complex(z);
+            if !(step.re.is_finite() && step.im.is_finite()) {
+                break;
+            }
+            z = z - step;
+            if tol.x_converged(step.abs(), z.abs()) {
+                break;
+            }
+        }
+        z
+    }
+
+    // All roots with Bairstow's method, which finds quadratic factors
+    // x^2 - r x - s using only real arithmetic
+    pub fn roots_bairstow(&self, tol: &Tolerance) -> Result<Vec<Complex>, RootError> {
+        if self.degree() == 0 {
+            return Err(RootError::ConstantPolynomial);
+        }
+        let mut p = self.clone();
+        let mut roots = Vec::new();
+        let (mut r, mut s) = (0.5, -0.5);
+
+        while p.degree() > 2 {
+            let mut converged = false;
+            for _ in 0..tol.max_iter {
+                let b = bairstow_recurrence(&p.coefficients, r, s);
+                // Partial derivatives of b1 and b0 with respect to r and s
+                let c = bairstow_recurrence(&b, r, s);
+                let det = c[2] * c[2] - c[3] * c[1];
+                if det == 0.0 {
+                    // Restart from another guess
+                    r += 1.0;
+                    s -= 1.0;
+                    continue;
+                }
+                let dr = (-b[1] * c[2] + b[0] * c[3]) / det;
+                let ds = (-b[0] * c[2] + b[1] * c[1]) / det;
+                r += dr;
+                s += ds;
+                if tol.x_converged(dr, r) && tol.x_converged(ds, s) {
+                    converged = true;
+                    break;
+                }
+            }
+            if !converged {
+                return Err(RootError::PolynomialNotConverged {
+                    iterations: tol.max_iter,
+                });
+            }
+            roots.extend(quadratic_roots(1.0, -r, -s));
+            p = p.deflate_quadratic(r, s).0;
+        }
+
+        let c = &p.coefficients;
+        match p.degree() {
+            2 => roots.extend(quadratic_roots(c[2], c[1], c[0])),
+            1 => roots.push(Complex::from(-c[0] / c[1])),
+            _ => {}
+        }
+        Ok(roots)
+    }
+}
+
+// b[n] = a[n], b[i] = a[i] + r b[i+1] + s b[i+2]
+fn bairstow_recurrence(a: &[f64], r: f64, s: f64) -> Vec<f64> {
+    let n = a.len();
+    let mut b = vec![0.0; n];
+    for i in (0..n).rev() {
+        let b1 = b.get(i + 1).copied().unwrap_or(0.0);
+        let b2 = b.get(i + 2).copied().unwrap_or(0.0);
+        b[i] = a[i] + r * b1 + s * b2;
+    }
+    b
+}
+
+// Roots of a x^2 + b x + c, computed without cancellation
+pub fn quadratic_roots(a: f64, b: f64, c: f64) -> [Complex; 2] {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant >= 0.0 {
+        let q = -0.5 * (b + discriminant.sqrt().copysign(b));
+        if q == 0.0 {
+            return [Complex::from(0.0), Complex::from(0.0)];
+        }
+        [Complex::from(q / a), Complex::from(c / q)]
+    } else {
+        let re = -b / (2.0 * a);
+        let im = (-discriminant).sqrt() / (2.0 * a).abs();
+        [Complex::new(re, im), Complex::new(re, -im)]
+    }
+}
+
+// Merges approximations that belong to one multiple root and averages them.
+// Approximations are merged when they are close or their inclusion disks overlap.
+fn group_roots(z: Vec<Complex>, radii: &[f64]) -> Vec<PolynomialRoot> {
+    let mut roots: Vec<(Complex, usize, f64)> = Vec::new();
+    for (zi, ri) in z.into_iter().zip(radii) {
+        match roots.iter_mut().find(|(c, m, r)| {
+            let center = *c / Complex::from(*m as f64);
+            let distance = (zi - center).abs();
+            distance <= MULTIPLICITY_RADIUS * (1.0 + center.abs()) || distance <= ri + r
+        }) {
+            Some((sum, m, r)) => {
+                *sum = *sum + zi;
+                *m += 1;
+                *r = r.max(*ri);
+            }
+            None => roots.push((zi, 1, *ri)),
+        }
+    }
+    let mut roots = roots
+        .into_iter()
+        .map(|(sum, m, _)| {
+            let mut value = sum / Complex::from(m as f64);
+            // A root this close to the real axis would share a group with its
+            // conjugate, so for real coefficients it has to be real
+            if value.im.abs() <= MULTIPLICITY_RADIUS * (1.0 + value.abs()) {
+                value.im = 0.0;
+            }
+            PolynomialRoot {
+                value,
+                multiplicity: m,
+            }
+        })
+        .collect::<Vec<PolynomialRoot>>();
+    roots.sort_by(|a, b| {
+        a.value
+            .re
+            .total_cmp(&b.value.re)
+            .then(a.value.im.total_cmp(&b.value.im))
+    });
+    roots
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, a)| **a != 0.0)
+            .map(|(i, a)| match i {
+                0 => format!("{}", a),
+                1 => format!("{}x", a),
+                _ => format!("{}x^{}", a, i),
+            })
+            .collect::<Vec<String>>();
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_root_is_found_once() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 100);
+        let roots = Polynomial::from_roots(&[1.0; 4])
+            .roots_aberth(&tol)
+            .unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].multiplicity, 4);
+        assert!((roots[0].value - Complex::from(1.0)).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn clustered_roots_are_kept_apart() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 100);
+        let expected = [1.0, 1.001, 1.002, 3.0];
+        let roots = Polynomial::from_roots(&expected)
+            .roots_aberth(&tol)
+            .unwrap();
+        assert_eq!(roots.len(), expected.len());
+        for (root, x) in roots.iter().zip(expected) {
+            assert_eq!(root.multiplicity, 1);
+            assert!(
+                (root.value - Complex::from(x)).abs() <= 1e-8,
+                "{}",
+                root.value
+            );
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+// Stopping criteria shared by all root finders. The x test passes when the
+// step (or bracket) is at most x_abs + x_rel * |x|, the f test when |f(x)| <= f_abs.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub x_abs: f64,
+    pub x_rel: f64,
+    pub f_abs: f64,
+    pub max_iter: usize,
+}
+
+impl Tolerance {
+    pub fn new(x_abs: f64, x_rel: f64, f_abs: f64, max_iter: usize) -> Tolerance {
+        Tolerance {
+            x_abs,
+            x_rel,
+            f_abs,
+            max_iter,
+        }
+    }
+
+    pub fn x_converged(&self, dx: f64, x: f64) -> bool {
+        dx.abs() <= self.x_abs + self.x_rel * x.abs()
+    }
+
+    pub fn f_converged(&self, fx: f64) -> bool {
+        fx.abs() <= self.f_abs
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance::new(1e-12, 1e-12, 0.0, 100)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Iterate {
+    pub x: f64,
+    pub fx: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Root {
+    pub x: f64,
+    // f(x) at the returned root
+    pub residual: f64,
+    pub iterations: usize,
+    // Width of the final bracket, None for open methods
+    pub bracket_width: Option<f64>,
+    // Every approximation produced, starting with the initial one
+    pub history: Vec<Iterate>,
+}
+
+impl Root {
+    // Estimates of the convergence order p from e_(k+1) ~ C e_k^p, using the
+    // distance to the final approximation as the error e_k
+    pub fn observed_order(&self) -> Vec<f64> {
+        let errors = self
+            .history
+            .iter()
+            .map(|it| (it.x - self.x).abs())
+            .take_while(|e| *e > 0.0)
+            .collect::<Vec<f64>>();
+        errors
+            .windows(3)
+            .map(|e| (e[2] / e[1]).ln() / (e[1] / e[0]).ln())
+            .collect()
+    }
+
+    // Ratios of successive steps |x_(k+1) - x_k| / |x_k - x_(k-1)|, which
+    // approach the contraction factor |g'(x)| of a linearly convergent iteration
+    pub fn contraction_factors(&self) -> Vec<f64> {
+        let steps = self
+            .history
+            .windows(2)
+            .map(|w| (w[1].x - w[0].x).abs())
+            .take_while(|s| *s > 0.0)
+            .collect::<Vec<f64>>();
+        steps.windows(2).map(|s| s[1] / s[0]).collect()
+    }
+
+    pub fn print(&self, method: &str) {
+        println!("Result for {} is: {}", method, self.x);
+        println!("Error: {:e}", self.residual);
+        print!("Iterations: {}", self.iterations);
+        match self.bracket_width {
+            Some(width) => println!(", bracket width: {:e}", width),
+            None => println!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RootError {
+    NoSignChange { fa: f64, fb: f64 },
+    InvalidInterval { a: f64, b: f64 },
+    NonFinite { x: f64 },
+    ZeroDerivative { x: f64 },
+    Cycling { x: f64, period: usize },
+    Diverged(Root),
+    ConstantPolynomial,
+    PolynomialNotConverged { iterations: usize },
+    // f changes sign at x without getting close to zero
+    Discontinuity { x: f64, fx: f64 },
+    // The best approximation reached before the iteration cap
+    MaxIterations(Root),
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RootError::NoSignChange { fa, fb } => write!(
+                f,
+                "the function has the same sign at the end points ({:e}, {:e})",
+                fa, fb
+            ),
+            RootError::InvalidInterval { a, b } => write!(f, "invalid interval [{}, {}]", a, b),
+            RootError::NonFinite { x } => write!(f, "the function is not finite at x = {}", x),
+            RootError::ZeroDerivative { x } => write!(f, "the derivative is zero at x = {}", x),
+            RootError::Cycling { x, period } => write!(
+                f,
+                "the iterates cycle with period {} around x = {}",
+                period, x
+            ),
+            RootError::Diverged(root) => write!(
+                f,
+                "the method diverged after {} iterations (last x = {})",
+                root.iterations, root.x
+            ),
+            RootError::ConstantPolynomial => write!(f, "a constant polynomial has no roots"),
+            RootError::PolynomialNotConverged { iterations } => write!(
+                f,
+                "the polynomial root finder did not converge in {} iterations",
+                iterations
+            ),
+            RootError::Discontinuity { x, fx } => write!(
+                f,
+                "the function has a discontinuity or pole near x = {} (f(x) = {:e})",
+                x, fx
+            ),
+            RootError::MaxIterations(root) => write!(
+                f,
+                "the method did not converge in {} iterations (last x = {})",
+                root.iterations, root.x
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RootError {}
+
+// Checks the end points of a bracketing interval and returns f(a), f(b)
+pub fn check_bracket(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> Result<(f64, f64), RootError> {
+    if !(a.is_finite() && b.is_finite()) || a >= b {
+        return Err(RootError::InvalidInterval { a, b });
+    }
+    let (fa, fb) = (f(a), f(b));
+    if !fa.is_finite() {
+        return Err(RootError::NonFinite { x: a });
+    }
+    if !fb.is_finite() {
+        return Err(RootError::NonFinite { x: b });
+    }
+    if fa * fb > 0.0 {
+        return Err(RootError::NoSignChange { fa, fb });
+    }
+    Ok((fa, fb))
+}
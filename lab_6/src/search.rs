@@ -0,0 +1,309 @@
+use crate::bracketing::{
+    anderson_bjorck_method, bisect_method, brent_method, illinois_method, ridders_method,
+};
+use crate::root::{Iterate, Root, RootError, Tolerance};
+
+const GOLDEN_RATIO: f64 = 0.618_033_988_749_894_9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bracket {
+    SignChange { a: f64, b: f64 },
+    // |f| has a local minimum close to zero but f does not change sign, e.g. a double root
+    Tangent { a: f64, b: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketSolver {
+    Bisection,
+    Illinois,
+    AndersonBjorck,
+    Brent,
+    Ridders,
+}
+
+impl BracketSolver {
+    pub fn solve(
+        &self,
+        f: impl Fn(f64) -> f64,
+        a: f64,
+        b: f64,
+        tol: &Tolerance,
+    ) -> Result<Root, RootError> {
+        match self {
+            BracketSolver::Bisection => bisect_method(f, a, b, tol),
+            BracketSolver::Illinois => illinois_method(f, a, b, tol),
+            BracketSolver::AndersonBjorck => anderson_bjorck_method(f, a, b, tol),
+            BracketSolver::Brent => brent_method(f, a, b, tol),
+            BracketSolver::Ridders => ridders_method(f, a, b, tol),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScanOptions {
+    // Number of equal intervals the subdivision starts from. Three samples can
+    // miss a whole oscillation, so this should resolve the shortest wavelength.
+    pub intervals: usize,
+    // Intervals are not split below this width; roots closer together than
+    // this can be reported as one tangent root
+    pub min_width: f64,
+    // A minimum of |f| at or below this value is reported as a tangent root
+    pub tangent_tolerance: f64,
+}
+
+impl Default for ScanOptions {
+    fn default() -> ScanOptions {
+        ScanOptions {
+            intervals: 32,
+            min_width: 1e-6,
+            tangent_tolerance: 1e-8,
+        }
+    }
+}
+
+// Scans [a, b] and returns the subintervals that contain a root, in increasing
+// order. Intervals are halved while f is not monotone on the end points and the
+// midpoint or while its curvature is large enough to reach zero between them,
+// which uncovers tangent roots and pairs of roots that a coarse scan would miss.
+pub fn find_brackets(
+    f: impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    options: &ScanOptions,
+) -> Result<Vec<Bracket>, RootError> {
+    if !(a.is_finite() && b.is_finite())
+        || a >= b
+        || options.intervals == 0
+        || options.min_width.is_nan()
+        || options.min_width <= 0.0
+    {
+        return Err(RootError::InvalidInterval { a, b });
+    }
+    let h = (b - a) / options.intervals as f64;
+    let x = (0..=options.intervals)
+        .map(|i| {
+            if i == options.intervals {
+                b
+            } else {
+                a + i as f64 * h
+            }
+        })
+        .collect::<Vec<f64>>();
+    let fx = x.iter().map(|xi| f(*xi)).collect::<Vec<f64>>();
+
+    let mut brackets = Vec::new();
+    if fx[0] == 0.0 {
+        brackets.push(Bracket::SignChange { a, b: a });
+    }
+    for i in 1..x.len() {
+        subdivide(
+            &f,
+            (x[i - 1], fx[i - 1]),
+            (x[i], fx[i]),
+            options,
+            &mut brackets,
+        );
+    }
+    Ok(brackets)
+}
+
+// Splits [a, b] at the midpoint until f looks simple enough there, then records
+// the sign changes and tangent roots of the final interval. A zero at the left
+// end belongs to the interval before it.
+fn subdivide(
+    f: &impl Fn(f64) -> f64,
+    (a, fa): (f64, f64),
+    (b, fb): (f64, f64),
+    options: &ScanOptions,
+    brackets: &mut Vec<Bracket>,
+) {
+    let m = 0.5 * (a + b);
+    let fm = f(m);
+    let finite = fa.is_finite() && fm.is_finite() && fb.is_finite();
+    let monotone = (fa <= fm && fm <= fb) || (fa >= fm && fm >= fb);
+    // Second difference, the error of the linear interpolation at m
+    let second = fa - 2.0 * fm + fb;
+    let smallest = fa.abs().min(fm.abs()).min(fb.abs());
+    let splittable = b - a > options.min_width && a < m && m < b;
+    if splittable && (!finite || !monotone || smallest <= second.abs()) {
+        subdivide(f, (a, fa), (m, fm), options, brackets);
+        subdivide(f, (m, fm), (b, fb), options, brackets);
+        return;
+    }
+    if !finite {
+        return;
+    }
+
+    for ((x0, f0), (x1, f1)) in [((a, fa), (m, fm)), ((m, fm), (b, fb))] {
+        if f0 * f1 < 0.0 {
+            brackets.push(Bracket::SignChange { a: x0, b: x1 });
+        } else if f1 == 0.0 {
+            brackets.push(Bracket::SignChange { a: x1, b: x1 });
+        }
+    }
+
+    // Without a sign change, the parabola through the three points tells if |f|
+    // has a minimum in [a, b) that touches or crosses zero
+    if fa * fm > 0.0 && fm * fb > 0.0 && second * fm > 0.0 {
+        // Vertex at m + t (b - a) / 2
+        let t = 0.5 * (fa - fb) / second;
+        let vertex = fm - (fb - fa).powi(2) / (8.0 * second);
+        if (-1.0..1.0).contains(&t)
+            && (vertex * fm <= 0.0 || vertex.abs() <= options.tangent_tolerance)
+        {
+            brackets.push(Bracket::Tangent { a, b });
+        }
+    }
+}
+
+// Golden-section search for a minimum of f on [a, b]; the returned residual is the minimum value
+fn minimize(f: impl Fn(f64) -> f64, a: f64, b: f64, tol: &Tolerance) -> Root {
+    let (mut a, mut b) = (a, b);
+    let mut history = Vec::new();
+    let mut x1 = b - GOLDEN_RATIO * (b - a);
+    let mut x2 = a + GOLDEN_RATIO * (b - a);
+    let (mut f1, mut f2) = (f(x1), f(x2));
+    for _ in 0..tol.max_iter {
+        if f1 < f2 {
+            b = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = b - GOLDEN_RATIO * (b - a);
+            f1 = f(x1);
+        } else {
+            a = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = a + GOLDEN_RATIO * (b - a);
+            f2 = f(x2);
+        }
+        let (x, fx) = if f1 < f2 { (x1, f1) } else { (x2, f2) };
+        history.push(Iterate { x, fx });
+        if tol.x_converged(b - a, x) {
+            break;
+        }
+    }
+    let last = history.last().copied().unwrap_or(Iterate {
+        x: 0.5 * (a + b),
+        fx: f(0.5 * (a + b)),
+    });
+    Root {
+        x: last.x,
+        residual: last.fx,
+        iterations: history.len(),
+        bracket_width: Some(b - a),
+        history,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RootSearch {
+    // Roots in increasing order
+    pub roots: Vec<Root>,
+    // Brackets the solver failed on; the roots found elsewhere are still kept
+    pub failures: Vec<(Bracket, RootError)>,
+}
+
+impl RootSearch {
+    pub fn print(&self) {
+        for root in &self.roots {
+            println!("  x = {}, f(x) = {:e}", root.x, root.residual);
+        }
+        for (bracket, e) in &self.failures {
+            match bracket {
+                Bracket::SignChange { a, b } | Bracket::Tangent { a, b } => {
+                    println!("  no root found in [{}, {}]: {}", a, b, e)
+                }
+            }
+        }
+    }
+}
+
+// Finds all roots in [a, b]: sign changes are refined with `solver`, tangent
+// brackets with a minimization of |f|
+pub fn find_all_roots(
+    f: impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    solver: BracketSolver,
+    options: &ScanOptions,
+    tol: &Tolerance,
+) -> Result<RootSearch, RootError> {
+    let mut roots: Vec<Root> = Vec::new();
+    let mut failures = Vec::new();
+    for bracket in find_brackets(&f, a, b, options)? {
+        let root = match bracket {
+            Bracket::SignChange { a, b } if a == b => Root {
+                x: a,
+                residual: 0.0,
+                iterations: 0,
+                bracket_width: Some(0.0),
+                history: Vec::new(),
+            },
+            Bracket::SignChange { a, b } => match solver.solve(&f, a, b, tol) {
+                // A pole or a jump also changes the sign, but there |f| does
+                // not become smaller than at the ends of the bracket
+                Ok(root)
+                    if !tol.f_converged(root.residual)
+                        && root.residual.abs() >= f(a).abs().min(f(b).abs()) =>
+                {
+                    failures.push((
+                        bracket,
+                        RootError::Discontinuity {
+                            x: root.x,
+                            fx: root.residual,
+                        },
+                    ));
+                    continue;
+                }
+                Ok(root) => root,
+                Err(e) => {
+                    failures.push((bracket, e));
+                    continue;
+                }
+            },
+            Bracket::Tangent { a, b } => {
+                let mut root = minimize(|x| f(x).abs(), a, b, tol);
+                root.residual = f(root.x);
+                root
+            }
+        };
+        // Neighbouring brackets can share a root at their common end point
+        let duplicate = roots.iter().any(|other| {
+            (root.x - other.x).abs() <= options.min_width
+                || tol.x_converged(root.x - other.x, root.x)
+        });
+        if !duplicate {
+            roots.push(root);
+        }
+    }
+    Ok(RootSearch { roots, failures })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poles_are_not_roots() {
+        let tol = Tolerance::new(1e-12, 1e-12, 0.0, 200);
+        let search = find_all_roots(
+            f64::tan,
+            0.5,
+            10.0,
+            BracketSolver::Brent,
+            &ScanOptions::default(),
+            &tol,
+        )
+        .unwrap();
+        let roots = search.roots.iter().map(|r| r.x).collect::<Vec<f64>>();
+        assert_eq!(roots.len(), 3, "roots = {:?}", roots);
+        for (k, x) in roots.iter().enumerate() {
+            assert!((x - (k + 1) as f64 * std::f64::consts::PI).abs() <= 1e-10);
+        }
+        assert_eq!(search.failures.len(), 3);
+        for (_, e) in &search.failures {
+            assert!(matches!(e, RootError::Discontinuity { .. }), "{}", e);
+        }
+    }
+}
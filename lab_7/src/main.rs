@@ -2,9 +2,11 @@ use plotly::common::{Mode, Title};
 use plotly::layout::{Axis, AxisType, Layout};
 use plotly::{Plot, Scatter};
 
+type Method = fn((f64, f64), usize, fn(f64) -> f64) -> f64;
+
 fn task() {
     let x_range = (0.0, 1.0);
-    let n_vec = (1..=7).map(|n| 10_i32.pow(n)).collect::<Vec<i32>>();
+    let n_vec = panel_counts();
     let f = function_1;
     let integral_analitical = function_1_analitical_integral(x_range);
     plot_integration(
@@ -34,8 +36,8 @@ fn task() {
 }
 
 fn plot_integration(
-    n_vec: Vec<i32>,
-    method: fn((f64, f64), f64, fn(f64) -> f64) -> f64,
+    n_vec: Vec<usize>,
+    method: Method,
     method_name: &str,
     analitycal_result: f64,
     f: fn(f64) -> f64,
@@ -44,7 +46,7 @@ fn plot_integration(
     let mut x = Vec::new();
     let mut y_error = Vec::new();
     for n in n_vec {
-        let integral = method(x_range, n as f64, f);
+        let integral = method(x_range, n, f);
         let error = absolute_error(integral, analitycal_result);
         x.push(n);
         y_error.push(error);
@@ -65,54 +67,52 @@ fn plot_integration(
     plot.show_image(plotly::ImageFormat::JPEG, 1000, 800);
 }
 
+fn panel_counts() -> Vec<usize> {
+    (1..=7).map(|n| 10_usize.pow(n)).collect()
+}
+
 fn absolute_error(integral: f64, integral_analitical: f64) -> f64 {
     (integral - integral_analitical).abs()
 }
 
 fn function_1(x: f64) -> f64 {
-    let fx = 1.0 / ((x.powi(2) + 1.0).sqrt());
-    fx
+    1.0 / ((x.powi(2) + 1.0).sqrt())
 }
 
 fn function_1_analitical_integral(x_range: (f64, f64)) -> f64 {
     x_range.1.asinh() - x_range.0.asinh()
 }
 
-fn integral_linear(x_range: (f64, f64), n: f64, f: fn(f64) -> f64) -> f64 {
-    let h = (x_range.1 - x_range.0) / n;
+// x_i = a + i h for the integer panel index i, so no rounding error accumulates
+fn node(x_range: (f64, f64), h: f64, i: usize) -> f64 {
+    x_range.0 + i as f64 * h
+}
+
+fn integral_linear(x_range: (f64, f64), n: usize, f: fn(f64) -> f64) -> f64 {
+    let h = (x_range.1 - x_range.0) / n as f64;
     let mut sum = 0.0;
-    let x_start = x_range.0;
-    let x_end = x_range.1;
-    let mut x_i = x_start;
-    while x_i < x_end {
-        sum += f(x_i) * h;
-        x_i += h;
+    for i in 0..n {
+        sum += f(node(x_range, h, i)) * h;
     }
     sum
 }
 
-fn integral_trapezoidal(x_range: (f64, f64), n: f64, f: fn(f64) -> f64) -> f64 {
-    let h = (x_range.1 - x_range.0) / n;
+fn integral_trapezoidal(x_range: (f64, f64), n: usize, f: fn(f64) -> f64) -> f64 {
+    let h = (x_range.1 - x_range.0) / n as f64;
     let mut sum = 0.0;
-    let x_start = x_range.0;
-    let x_end = x_range.1;
-    let mut x_i = x_start;
-    while x_i < x_end {
-        sum += (f(x_i) + f(x_i + h)) * h / 2.0;
-        x_i += h;
+    for i in 0..n {
+        let (x_i, x_next) = (node(x_range, h, i), node(x_range, h, i + 1));
+        sum += (f(x_i) + f(x_next)) * h / 2.0;
     }
     sum
 }
 
-fn simpson13(x_range: (f64, f64), n: f64, f: fn(f64) -> f64) -> f64 {
-    let h = (x_range.1 - x_range.0) / n;
+fn simpson13(x_range: (f64, f64), n: usize, f: fn(f64) -> f64) -> f64 {
+    let h = (x_range.1 - x_range.0) / n as f64;
     let mut sum = 0.0;
-    let x_start = x_range.0;
-    let x_end = x_range.1;
-    let mut x_i = x_start;
-    while x_i < x_end {
-        sum += (f(x_i) + 4.0 * f(x_i + h / 2.0) + f(x_i + h)) * h / 6.0;
-        x_i += h;
+    for i in 0..n {
+        let (x_i, x_next) = (node(x_range, h, i), node(x_range, h, i + 1));
+        sum += (f(x_i) + 4.0 * f((x_i + x_next) / 2.0) + f(x_next)) * h / 6.0;
     }
     sum
 }
@@ -120,3 +120,75 @@ fn simpson13(x_range: (f64, f64), n: f64, f: fn(f64) -> f64) -> f64 {
 fn main() {
     task();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    thread_local! {
+        // Calls of `counted_one` and the largest x it saw. Every test runs on its
+        // own thread, so other tests cannot change the counts.
+        static EVALUATIONS: Cell<usize> = const { Cell::new(0) };
+        static LARGEST_X: Cell<f64> = const { Cell::new(f64::NEG_INFINITY) };
+    }
+
+    fn counted_one(x: f64) -> f64 {
+        EVALUATIONS.set(EVALUATIONS.get() + 1);
+        LARGEST_X.set(LARGEST_X.get().max(x));
+        1.0
+    }
+
+    // The rules used to advance x_i += h while x_i < b, so rounding could add or
+    // drop a whole panel. Each rule evaluates f a fixed number of times per panel,
+    // and the rules that evaluate f at x_n have to hit b.
+    #[test]
+    fn rules_use_n_panels() {
+        let x_range = (0.0, 1.0);
+        let ulp = f64::EPSILON * x_range.1;
+        let methods: [(&str, Method, usize, bool); 3] = [
+            ("Linear", integral_linear, 1, false),
+            ("Trapezoidal", integral_trapezoidal, 2, true),
+            ("Simpson 1/3", simpson13, 3, true),
+        ];
+        for (name, method, evaluations_per_panel, reaches_b) in methods {
+            for n in panel_counts() {
+                EVALUATIONS.set(0);
+                LARGEST_X.set(f64::NEG_INFINITY);
+                method(x_range, n, counted_one);
+                assert_eq!(
+                    EVALUATIONS.get(),
+                    n * evaluations_per_panel,
+                    "{} with n = {}",
+                    name,
+                    n
+                );
+                if reaches_b {
+                    assert!(
+                        (LARGEST_X.get() - x_range.1).abs() <= ulp,
+                        "{} with n = {} ends at {}",
+                        name,
+                        n,
+                        LARGEST_X.get()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn last_node_is_b() {
+        let x_range = (0.0, 1.0);
+        for n in panel_counts() {
+            let h = (x_range.1 - x_range.0) / n as f64;
+            let last = node(x_range, h, n);
+            assert!(
+                (last - x_range.1).abs() <= f64::EPSILON * x_range.1,
+                "n = {}: x_n = {}",
+                n,
+                last
+            );
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use crate::equation::{EquationSystem, SystemError};
+use crate::residual::{norm_1, ResidualCheck};
+
+// a_i x_(i-1) + b_i x_i + c_i x_(i+1) = r_i, where `lower` holds a_2..a_n
+// and `upper` holds c_1..c_(n-1)
+#[derive(Debug, Clone)]
+pub struct TridiagonalSystem {
+    pub lower: Vec<f64>,
+    pub diagonal: Vec<f64>,
+    pub upper: Vec<f64>,
+    pub r: Vec<f64>,
+}
+
+impl TridiagonalSystem {
+    pub fn new(
+        lower: Vec<f64>,
+        diagonal: Vec<f64>,
+        upper: Vec<f64>,
+        r: Vec<f64>,
+    ) -> Result<TridiagonalSystem, SystemError> {
+        let n = diagonal.len();
+        if n == 0 {
+            return Err(SystemError::Empty);
+        }
+        for len in [lower.len(), upper.len()] {
+            if len != n - 1 {
+                return Err(SystemError::DimensionMismatch {
+                    expected: n - 1,
+                    found: len,
+                });
+            }
+        }
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+        Ok(TridiagonalSystem {
+            lower,
+            diagonal,
+            upper,
+            r,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.diagonal.len()
+    }
+
+    // Thomas algorithm, O(n). No pivoting is done, which is safe for
+    // diagonally dominant systems.
+    pub fn solve(&self) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        let mut c = vec![0.0; n];
+        let mut d = vec![0.0; n];
+
+        for i in 0..n {
+            let a = if i > 0 { self.lower[i - 1] } else { 0.0 };
+            let previous_c = if i > 0 { c[i - 1] } else { 0.0 };
+            let previous_d = if i > 0 { d[i - 1] } else { 0.0 };
+            let pivot = self.diagonal[i] - a * previous_c;
+            if pivot == 0.0 || !pivot.is_finite() {
+                return Err(SystemError::ZeroPivot { column: i, pivot });
+            }
+            if i < n - 1 {
+                c[i] = self.upper[i] / pivot;
+            }
+            d[i] = (self.r[i] - a * previous_d) / pivot;
+        }
+
+        let mut x = d;
+        for i in (0..n - 1).rev() {
+            x[i] -= c[i] * x[i + 1];
+        }
+        Ok(x)
+    }
+
+    pub fn multiply(&self, x: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        if x.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: x.len(),
+            });
+        }
+        Ok((0..n)
+            .map(|i| {
+                let mut sum = self.diagonal[i] * x[i];
+                if i > 0 {
+                    sum += self.lower[i - 1] * x[i - 1];
+                }
+                if i < n - 1 {
+                    sum += self.upper[i] * x[i + 1];
+                }
+                sum
+            })
+            .collect())
+    }
+
+    pub fn check_solution(&self, x: &[f64], tolerance: f64) -> Result<ResidualCheck, SystemError> {
+        let residual = self
+            .multiply(x)?
+            .iter()
+            .zip(&self.r)
+            .map(|(ax, r)| r - ax)
+            .collect();
+        let matrix_norm = (0..self.size())
+            .map(|i| {
+                self.diagonal[i].abs()
+                    + if i > 0 { self.lower[i - 1].abs() } else { 0.0 }
+                    + self.upper.get(i).map_or(0.0, |c| c.abs())
+            })
+            .fold(0.0, f64::max);
+        Ok(ResidualCheck::new(
+            residual,
+            x,
+            &self.r,
+            matrix_norm,
+            tolerance,
+        ))
+    }
+}
+
+// Band storage: row i keeps the entries of columns i - lower_bandwidth ..= i + upper_bandwidth
+#[derive(Debug, Clone)]
+pub struct BandedSystem {
+    pub lower_bandwidth: usize,
+    pub upper_bandwidth: usize,
+    pub bands: Vec<Vec<f64>>,
+    pub r: Vec<f64>,
+}
+
+impl BandedSystem {
+    pub fn new(
+        lower_bandwidth: usize,
+        upper_bandwidth: usize,
+        bands: Vec<Vec<f64>>,
+        r: Vec<f64>,
+    ) -> Result<BandedSystem, SystemError> {
+        let n = bands.len();
+        if n == 0 {
+            return Err(SystemError::Empty);
+        }
+        let width = lower_bandwidth + upper_bandwidth + 1;
+        for (row, band) in bands.iter().enumerate() {
+            if band.len() != width {
+                return Err(SystemError::NotSquare {
+                    row,
+                    expected: width,
+                    found: band.len(),
+                });
+            }
+        }
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+        Ok(BandedSystem {
+            lower_bandwidth,
+            upper_bandwidth,
+            bands,
+            r,
+        })
+    }
+
+    // Entries outside of the band are dropped
+    pub fn from_equation_system(
+        system: &EquationSystem,
+        lower_bandwidth: usize,
+        upper_bandwidth: usize,
+    ) -> BandedSystem {
+        let n = system.size();
+        let bands = system
+            .equations
+            .iter()
+            .enumerate()
+            .map(|(i, eq)| {
+                (0..=lower_bandwidth + upper_bandwidth)
+                    .map(|k| {
+                        (i + k)
+                            .checked_sub(lower_bandwidth)
+                            .filter(|j| *j < n)
+                            .map_or(0.0, |j| eq.coefficients[j])
+                    })
+                    .collect()
+            })
+            .collect();
+        BandedSystem {
+            lower_bandwidth,
+            upper_bandwidth,
+            bands,
+            r: system.rhs(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.bands.len()
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        if j + self.lower_bandwidth < i || j > i + self.upper_bandwidth {
+            0.0
+        } else {
+            self.bands[i][j + self.lower_bandwidth - i]
+        }
+    }
+
+    // Gaussian elimination inside the band without pivoting, O(n * kl * ku)
+    pub fn solve(&self) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        let (kl, ku) = (self.lower_bandwidth, self.upper_bandwidth);
+        let mut bands = self.bands.clone();
+        let mut r = self.r.clone();
+
+        for k in 0..n {
+            let pivot = bands[k][kl];
+            if pivot == 0.0 || !pivot.is_finite() {
+                return Err(SystemError::ZeroPivot { column: k, pivot });
+            }
+            for i in (k + 1)..n.min(k + kl + 1) {
+                let factor = bands[i][k + kl - i] / pivot;
+                for j in k..n.min(k + ku + 1) {
+                    bands[i][j + kl - i] -= factor * bands[k][j + kl - k];
+                }
+                r[i] -= factor * r[k];
+            }
+        }
+
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n.min(i + ku + 1))
+                .map(|j| bands[i][j + kl - i] * x[j])
+                .sum();
+            x[i] = (r[i] - sum) / bands[i][kl];
+        }
+        Ok(x)
+    }
+
+    pub fn multiply(&self, x: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        if x.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: x.len(),
+            });
+        }
+        Ok((0..n)
+            .map(|i| {
+                let first = i.saturating_sub(self.lower_bandwidth);
+                let last = n.min(i + self.upper_bandwidth + 1);
+                (first..last).map(|j| self.get(i, j) * x[j]).sum()
+            })
+            .collect())
+    }
+
+    pub fn check_solution(&self, x: &[f64], tolerance: f64) -> Result<ResidualCheck, SystemError> {
+        let residual = self
+            .multiply(x)?
+            .iter()
+            .zip(&self.r)
+            .map(|(ax, r)| r - ax)
+            .collect();
+        let matrix_norm = self
+            .bands
+            .iter()
+            .map(|band| norm_1(band))
+            .fold(0.0, f64::max);
+        Ok(ResidualCheck::new(
+            residual,
+            x,
+            &self.r,
+            matrix_norm,
+            tolerance,
+        ))
+    }
+}
@@ -0,0 +1,346 @@
+use crate::equation::{EquationSystem, SystemError};
+
+// A = L L^T for symmetric positive-definite matrices
+#[derive(Debug, Clone)]
+pub struct CholeskyDecomposition {
+    l: Vec<Vec<f64>>,
+}
+
+// P A P^T = L D L^T for symmetric matrices with Bunch-Kaufman pivoting.
+// L has a unit diagonal and D is block diagonal with 1x1 and 2x2 blocks, so
+// indefinite matrices with zeros on the diagonal can be factored as well.
+// The 2x2 blocks are stored in `d` and `e`, the diagonal and subdiagonal of D.
+#[derive(Debug, Clone)]
+pub struct LdltDecomposition {
+    l: Vec<Vec<f64>>,
+    d: Vec<f64>,
+    e: Vec<f64>,
+    permutation: Vec<usize>,
+}
+
+impl CholeskyDecomposition {
+    pub fn new(system: &EquationSystem) -> Result<CholeskyDecomposition, SystemError> {
+        system.check_symmetric()?;
+        let a = system.matrix();
+        let n = system.size();
+        let tolerance = system.singularity_tolerance();
+        let mut l = vec![vec![0.0; n]; n];
+
+        for j in 0..n {
+            let diagonal = a[j][j] - (0..j).map(|k| l[j][k] * l[j][k]).sum::<f64>();
+            if diagonal <= tolerance {
+                return Err(SystemError::NotPositiveDefinite {
+                    column: j,
+                    pivot: diagonal,
+                });
+            }
+            l[j][j] = diagonal.sqrt();
+            for i in (j + 1)..n {
+                let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+
+        Ok(CholeskyDecomposition { l })
+    }
+
+    pub fn l(&self) -> &[Vec<f64>] {
+        &self.l
+    }
+
+    pub fn solve(&self, r: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.l.len();
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+
+        // Ly = r
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|j| self.l[i][j] * y[j]).sum();
+            y[i] = (r[i] - sum) / self.l[i][i];
+        }
+
+        // L^T x = y
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n).map(|j| self.l[j][i] * x[j]).sum();
+            x[i] = (y[i] - sum) / self.l[i][i];
+        }
+        Ok(x)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.l
+            .iter()
+            .enumerate()
+            .fold(1.0, |det, (i, row)| det * row[i] * row[i])
+    }
+}
+
+impl LdltDecomposition {
+    pub fn new(system: &EquationSystem) -> Result<LdltDecomposition, SystemError> {
+        system.check_symmetric()?;
+        let mut a = system.matrix();
+        let n = system.size();
+        let tolerance = system.singularity_tolerance();
+        // Bounds the growth of the entries of L and of the Schur complements
+        let alpha = (1.0 + 17.0_f64.sqrt()) / 8.0;
+        let mut l = vec![vec![0.0; n]; n];
+        let mut d = vec![0.0; n];
+        let mut e = vec![0.0; n.saturating_sub(1)];
+        let mut permutation = (0..n).collect::<Vec<usize>>();
+
+        let mut k = 0;
+        while k < n {
+            // Largest entry below the diagonal in column k
+            let r = ((k + 1)..n).fold(k, |best, i| {
+                if best == k || a[i][k].abs() > a[best][k].abs() {
+                    i
+                } else {
+                    best
+                }
+            });
+            let lambda = if r == k { 0.0 } else { a[r][k].abs() };
+            if a[k][k].abs().max(lambda) <= tolerance {
+                return Err(SystemError::Singular {
+                    column: k,
+                    pivot: a[k][k],
+                });
+            }
+
+            let two_by_two = if a[k][k].abs() >= alpha * lambda {
+                false
+            } else {
+                // Largest off-diagonal entry in column r of the remaining matrix
+                let sigma = (k..n)
+                    .filter(|i| *i != r)
+                    .fold(0.0_f64, |m, i| m.max(a[i][r].abs()));
+                if a[k][k].abs() * sigma >= alpha * lambda * lambda {
+                    false
+                } else if a[r][r].abs() >= alpha * sigma {
+                    symmetric_swap(&mut a, &mut l, &mut permutation, k, r);
+                    false
+                } else {
+                    symmetric_swap(&mut a, &mut l, &mut permutation, k + 1, r);
+                    true
+                }
+            };
+
+            l[k][k] = 1.0;
+            if two_by_two {
+                l[k + 1][k + 1] = 1.0;
+                let (p, q, t) = (a[k][k], a[k + 1][k], a[k + 1][k + 1]);
+                let det = p * t - q * q;
+                d[k] = p;
+                d[k + 1] = t;
+                e[k] = q;
+                // Rows of L are the rows of the column block times the inverse of the 2x2 block
+                for i in (k + 2)..n {
+                    let (u, v) = (a[i][k], a[i][k + 1]);
+                    l[i][k] = (u * t - v * q) / det;
+                    l[i][k + 1] = (v * p - u * q) / det;
+                }
+                let columns = a
+                    .iter()
+                    .map(|row| (row[k], row[k + 1]))
+                    .collect::<Vec<(f64, f64)>>();
+                for i in (k + 2)..n {
+                    let (f, g) = (l[i][k], l[i][k + 1]);
+                    for (aij, (u, v)) in a[i].iter_mut().zip(&columns).skip(k + 2) {
+                        *aij -= f * u + g * v;
+                    }
+                }
+                k += 2;
+            } else {
+                d[k] = a[k][k];
+                for i in (k + 1)..n {
+                    l[i][k] = a[i][k] / d[k];
+                }
+                let column = a.iter().map(|row| row[k]).collect::<Vec<f64>>();
+                for i in (k + 1)..n {
+                    let factor = l[i][k];
+                    for (aij, ajk) in a[i].iter_mut().zip(&column).skip(k + 1) {
+                        *aij -= factor * ajk;
+                    }
+                }
+                k += 1;
+            }
+        }
+
+        Ok(LdltDecomposition {
+            l,
+            d,
+            e,
+            permutation,
+        })
+    }
+
+    pub fn l(&self) -> &[Vec<f64>] {
+        &self.l
+    }
+
+    // Diagonal of D
+    pub fn d(&self) -> &[f64] {
+        &self.d
+    }
+
+    // Subdiagonal of D, nonzero only inside the 2x2 blocks
+    pub fn e(&self) -> &[f64] {
+        &self.e
+    }
+
+    // Row i of P A P^T is row permutation[i] of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    // First index and size of every block of D
+    fn blocks(&self) -> Vec<(usize, usize)> {
+        let mut blocks = Vec::new();
+        let mut k = 0;
+        while k < self.d.len() {
+            let size = if self.e.get(k).is_some_and(|e| *e != 0.0) {
+                2
+            } else {
+                1
+            };
+            blocks.push((k, size));
+            k += size;
+        }
+        blocks
+    }
+
+    // Number of positive and negative eigenvalues (Sylvester's law of inertia).
+    // A 2x2 block with a negative determinant has one eigenvalue of each sign.
+    pub fn inertia(&self) -> (usize, usize) {
+        let positive = self
+            .blocks()
+            .iter()
+            .map(|(k, size)| match size {
+                1 => usize::from(self.d[*k] > 0.0),
+                _ => {
+                    let (p, q, t) = (self.d[*k], self.e[*k], self.d[*k + 1]);
+                    if p * t - q * q < 0.0 {
+                        1
+                    } else if p + t > 0.0 {
+                        2
+                    } else {
+                        0
+                    }
+                }
+            })
+            .sum::<usize>();
+        (positive, self.d.len() - positive)
+    }
+
+    pub fn is_positive_definite(&self) -> bool {
+        self.inertia().1 == 0
+    }
+
+    pub fn solve(&self, r: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.d.len();
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+
+        // Lz = Pr
+        let mut z = vec![0.0; n];
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|j| self.l[i][j] * z[j]).sum();
+            z[i] = r[self.permutation[i]] - sum;
+        }
+
+        // Dw = z, one block at a time
+        let mut w = vec![0.0; n];
+        for (k, size) in self.blocks() {
+            if size == 1 {
+                w[k] = z[k] / self.d[k];
+            } else {
+                let (p, q, t) = (self.d[k], self.e[k], self.d[k + 1]);
+                let det = p * t - q * q;
+                w[k] = (t * z[k] - q * z[k + 1]) / det;
+                w[k + 1] = (p * z[k + 1] - q * z[k]) / det;
+            }
+        }
+
+        // L^T P x = w
+        let mut y = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n).map(|j| self.l[j][i] * y[j]).sum();
+            y[i] = w[i] - sum;
+        }
+        let mut x = vec![0.0; n];
+        for (i, p) in self.permutation.iter().enumerate() {
+            x[*p] = y[i];
+        }
+        Ok(x)
+    }
+
+    // The symmetric permutation does not change the determinant
+    pub fn determinant(&self) -> f64 {
+        self.blocks()
+            .iter()
+            .map(|(k, size)| match size {
+                1 => self.d[*k],
+                _ => self.d[*k] * self.d[*k + 1] - self.e[*k] * self.e[*k],
+            })
+            .product()
+    }
+}
+
+// Swaps rows and columns i and j of A together with the rows of L computed so far
+fn symmetric_swap(
+    a: &mut [Vec<f64>],
+    l: &mut [Vec<f64>],
+    permutation: &mut [usize],
+    i: usize,
+    j: usize,
+) {
+    if i == j {
+        return;
+    }
+    a.swap(i, j);
+    for row in a.iter_mut() {
+        row.swap(i, j);
+    }
+    l.swap(i, j);
+    permutation.swap(i, j);
+}
+
+impl EquationSystem {
+    pub fn is_symmetric(&self) -> bool {
+        self.check_symmetric().is_ok()
+    }
+
+    fn check_symmetric(&self) -> Result<(), SystemError> {
+        let a = self.matrix();
+        let tolerance = self.singularity_tolerance();
+        for (i, row) in a.iter().enumerate() {
+            for (j, other) in a.iter().enumerate().skip(i + 1) {
+                if (row[j] - other[i]).abs() > tolerance {
+                    return Err(SystemError::NotSymmetric { row: i, column: j });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn cholesky(&self) -> Result<CholeskyDecomposition, SystemError> {
+        CholeskyDecomposition::new(self)
+    }
+
+    pub fn ldlt(&self) -> Result<LdltDecomposition, SystemError> {
+        LdltDecomposition::new(self)
+    }
+
+    pub fn solve_cholesky(&self) -> Result<Vec<f64>, SystemError> {
+        self.cholesky()?.solve(&self.rhs())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::equation::{EquationSystem, SystemError};
+use crate::lu::LuDecomposition;
+use crate::residual::{norm_1, norm_inf};
+
+// Above this size the condition number is estimated instead of computed from the inverse
+pub const EXACT_CONDITION_LIMIT: usize = 100;
+
+pub fn matrix_norm_1(a: &[Vec<f64>]) -> f64 {
+    let n = a.first().map_or(0, |row| row.len());
+    (0..n)
+        .map(|j| a.iter().map(|row| row[j].abs()).sum::<f64>())
+        .fold(0.0, f64::max)
+}
+
+pub fn matrix_norm_inf(a: &[Vec<f64>]) -> f64 {
+    a.iter().map(|row| norm_1(row)).fold(0.0, f64::max)
+}
+
+pub fn matrix_norm_frobenius(a: &[Vec<f64>]) -> f64 {
+    a.iter()
+        .flat_map(|row| row.iter())
+        .map(|x| x * x)
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorBound {
+    // Condition number in the 1-norm
+    pub condition_number: f64,
+    pub estimated: bool,
+    // ||r - Ax|| / ||r|| in the 1-norm
+    pub relative_residual: f64,
+    // Upper bound on ||x - x_true|| / ||x_true||
+    pub bound: f64,
+}
+
+impl LuDecomposition {
+    // Hager's estimate of ||A^-1||_1, which needs only a few solves with the factors
+    pub fn inverse_norm_estimate(&self) -> Result<f64, SystemError> {
+        let n = self.size();
+        let mut x = vec![1.0 / n as f64; n];
+        let mut inverse_norm = 0.0;
+
+        for _ in 0..5 {
+            let y = self.solve(&x)?;
+            inverse_norm = norm_1(&y);
+            let sign = y
+                .iter()
+                .map(|v| if *v >= 0.0 { 1.0 } else { -1.0 })
+                .collect::<Vec<f64>>();
+            let z = self.solve_transpose(&sign)?;
+            let z_x: f64 = z.iter().zip(&x).map(|(a, b)| a * b).sum();
+            if norm_inf(&z) <= z_x {
+                break;
+            }
+            let j = (0..n).fold(
+                0,
+                |best, i| if z[i].abs() > z[best].abs() { i } else { best },
+            );
+            x = vec![0.0; n];
+            x[j] = 1.0;
+        }
+
+        Ok(inverse_norm)
+    }
+}
+
+impl EquationSystem {
+    pub fn matrix_norm_1(&self) -> f64 {
+        matrix_norm_1(&self.matrix())
+    }
+
+    // Exact 1-norm condition number, ||A||_1 ||A^-1||_1
+    pub fn condition_number_exact(&self) -> Result<f64, SystemError> {
+        let inverse = self.lu_decompose()?.inverse();
+        Ok(self.matrix_norm_1() * matrix_norm_1(&inverse))
+    }
+
+    // Hager's estimate of the condition number, see LuDecomposition::inverse_norm_estimate
+    pub fn condition_number_estimate(&self) -> Result<f64, SystemError> {
+        Ok(self.matrix_norm_1() * self.lu_decompose()?.inverse_norm_estimate()?)
+    }
+
+    pub fn condition_number(&self) -> Result<f64, SystemError> {
+        if self.size() <= EXACT_CONDITION_LIMIT {
+            self.condition_number_exact()
+        } else {
+            self.condition_number_estimate()
+        }
+    }
+
+    // ||x - x_true|| / ||x_true|| <= cond(A) ||r - Ax|| / ||r||, with the residual
+    // enlarged by the rounding error made while computing it in floating point
+    pub fn forward_error_bound(&self, x: &[f64]) -> Result<ErrorBound, SystemError> {
+        let residual = self.residual(x)?;
+        let rhs_norm = norm_1(&self.rhs()).max(f64::MIN_POSITIVE);
+        let rounding =
+            self.size() as f64 * f64::EPSILON * (self.matrix_norm_1() * norm_1(x) + rhs_norm);
+        let relative_residual = norm_1(&residual) / rhs_norm;
+        let condition_number = self.condition_number()?;
+        Ok(ErrorBound {
+            condition_number,
+            estimated: self.size() > EXACT_CONDITION_LIMIT,
+            relative_residual,
+            bound: condition_number * (norm_1(&residual) + rounding) / rhs_norm,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hilbert matrix with the right-hand side chosen so that the exact solution is all ones
+    fn hilbert(n: usize) -> EquationSystem {
+        let a = (0..n)
+            .map(|i| (0..n).map(|j| 1.0 / (i + j + 1) as f64).collect())
+            .collect();
+        let r = (0..n)
+            .map(|i| (0..n).map(|j| 1.0 / (i + j + 1) as f64).sum())
+            .collect();
+        EquationSystem::from_matrix(a, r).unwrap()
+    }
+
+    #[test]
+    fn forward_error_is_within_bound() {
+        for n in 2..=12 {
+            let system = hilbert(n);
+            let x = system.solve_naive_gauss_el().unwrap();
+            let error = norm_1(&x.iter().map(|xi| xi - 1.0).collect::<Vec<f64>>()) / n as f64;
+            let bound = system.forward_error_bound(&x).unwrap();
+            assert!(
+                error <= bound.bound,
+                "n = {}: error {:e} above bound {:e}",
+                n,
+                error,
+                bound.bound
+            );
+            // The bound is useful only if it is not wildly pessimistic. Some solves
+            // come out far more accurate than the worst case, e.g. 2e4 times for n = 5.
+            assert!(
+                bound.bound <= 1e4 * n as f64 * error.max(f64::EPSILON),
+                "n = {}: bound {:e} far above error {:e}",
+                n,
+                bound.bound,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn hager_estimate_is_close_to_exact() {
+        for n in 2..=12 {
+            let system = hilbert(n);
+            let exact = system.condition_number_exact().unwrap();
+            let estimate = system.condition_number_estimate().unwrap();
+            assert!(
+                estimate >= exact / 3.0 && estimate <= exact * 3.0,
+                "n = {}: estimate {:e}, exact {:e}",
+                n,
+                estimate,
+                exact
+            );
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemError {
+    Empty,
+    NotSquare {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    NonFinite {
+        row: usize,
+    },
+    DimensionMismatch {
+        expected: usize,
+        found: usize,
+    },
+    ZeroPivot {
+        column: usize,
+        pivot: f64,
+    },
+    Singular {
+        column: usize,
+        pivot: f64,
+    },
+    InvalidRelaxation(f64),
+    NotSymmetric {
+        row: usize,
+        column: usize,
+    },
+    NotPositiveDefinite {
+        column: usize,
+        pivot: f64,
+    },
+    IllConditioned {
+        condition_number: f64,
+    },
+    Diverged {
+        iterations: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pivoting {
+    None,
+    Partial,
+    Scaled,
+}
+
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SystemError::Empty => write!(f, "the system has no equations"),
+            SystemError::NotSquare {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "equation {} has {} coefficients, expected {}",
+                row + 1,
+                found,
+                expected
+            ),
+            SystemError::NonFinite { row } => {
+                write!(f, "equation {} contains a NaN or infinite value", row + 1)
+            }
+            SystemError::DimensionMismatch { expected, found } => {
+                write!(f, "expected a vector of length {}, got {}", expected, found)
+            }
+            SystemError::ZeroPivot { column, pivot } => write!(
+                f,
+                "zero or tiny pivot {:e} in column {}, try pivoting",
+                pivot,
+                column + 1
+            ),
+            SystemError::Singular { column, pivot } => write!(
+                f,
+                "matrix is singular or nearly singular (pivot {:e} in column {})",
+                pivot,
+                column + 1
+            ),
+            SystemError::InvalidRelaxation(omega) => write!(
+                f,
+                "relaxation factor {} is outside of the (0, 2) interval",
+                omega
+            ),
+            SystemError::NotSymmetric { row, column } => write!(
+                f,
+                "matrix is not symmetric, a{}{} differs from a{}{}",
+                row + 1,
+                column + 1,
+                column + 1,
+                row + 1
+            ),
+            SystemError::NotPositiveDefinite { column, pivot } => write!(
+                f,
+                "matrix is not positive definite (diagonal {:e} in column {})",
+                pivot,
+                column + 1
+            ),
+            SystemError::IllConditioned { condition_number } => write!(
+                f,
+                "matrix is too ill-conditioned for double precision (condition number {:e})",
+                condition_number
+            ),
+            SystemError::Diverged { iterations } => write!(
+                f,
+                "the iteration diverged, the residual is not finite after {} iterations",
+                iterations
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SystemError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equation {
+    pub coefficients: Vec<f64>,
+    pub r: f64,
+}
+
+impl Equation {
+    pub fn new(coefficients: Vec<f64>, r: f64) -> Equation {
+        Equation { coefficients, r }
+    }
+
+    pub fn len(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    pub fn multiply(&self, value: f64) -> Equation {
+        Equation {
+            coefficients: self.coefficients.iter().map(|a| a * value).collect(),
+            r: self.r * value,
+        }
+    }
+
+    pub fn divide(&self, value: f64) -> Equation {
+        Equation {
+            coefficients: self.coefficients.iter().map(|a| a / value).collect(),
+            r: self.r / value,
+        }
+    }
+
+    pub fn add(&self, other: &Equation) -> Equation {
+        Equation {
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(&other.coefficients)
+                .map(|(a, b)| a + b)
+                .collect(),
+            r: self.r + other.r,
+        }
+    }
+
+    pub fn subtract(&self, other: &Equation) -> Equation {
+        Equation {
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(&other.coefficients)
+                .map(|(a, b)| a - b)
+                .collect(),
+            r: self.r - other.r,
+        }
+    }
+
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        self.coefficients.iter().zip(x).map(|(a, xi)| a * xi).sum()
+    }
+
+    pub fn print(&self) {
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(i, a)| format!("{}x{}", a, i + 1))
+            .collect::<Vec<String>>();
+        println!("{} = {}", terms.join(" + "), self.r);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquationSystem {
+    pub equations: Vec<Equation>,
+}
+
+impl EquationSystem {
+    pub fn new(equations: Vec<Equation>) -> Result<EquationSystem, SystemError> {
+        let n = equations.len();
+        if n == 0 {
+            return Err(SystemError::Empty);
+        }
+        for (row, eq) in equations.iter().enumerate() {
+            if eq.len() != n {
+                return Err(SystemError::NotSquare {
+                    row,
+                    expected: n,
+                    found: eq.len(),
+                });
+            }
+            if !eq.r.is_finite() || eq.coefficients.iter().any(|a| !a.is_finite()) {
+                return Err(SystemError::NonFinite { row });
+            }
+        }
+        Ok(EquationSystem { equations })
+    }
+
+    // Builds the system from rows of the augmented matrix [A | r]
+    pub fn from_augmented(rows: Vec<Vec<f64>>) -> Result<EquationSystem, SystemError> {
+        let n = rows.len();
+        let mut equations = Vec::with_capacity(n);
+        for (row, mut values) in rows.into_iter().enumerate() {
+            if values.len() != n + 1 {
+                return Err(SystemError::NotSquare {
+                    row,
+                    expected: n,
+                    found: values.len().saturating_sub(1),
+                });
+            }
+            let r = values.pop().unwrap();
+            equations.push(Equation::new(values, r));
+        }
+        EquationSystem::new(equations)
+    }
+
+    pub fn from_matrix(a: Vec<Vec<f64>>, r: Vec<f64>) -> Result<EquationSystem, SystemError> {
+        if a.len() != r.len() {
+            return Err(SystemError::DimensionMismatch {
+                expected: a.len(),
+                found: r.len(),
+            });
+        }
+        let equations = a
+            .into_iter()
+            .zip(r)
+            .map(|(coefficients, r)| Equation::new(coefficients, r))
+            .collect();
+        EquationSystem::new(equations)
+    }
+
+    pub fn size(&self) -> usize {
+        self.equations.len()
+    }
+
+    pub fn matrix(&self) -> Vec<Vec<f64>> {
+        self.equations
+            .iter()
+            .map(|eq| eq.coefficients.clone())
+            .collect()
+    }
+
+    pub fn rhs(&self) -> Vec<f64> {
+        self.equations.iter().map(|eq| eq.r).collect()
+    }
+
+    pub fn print(&self) {
+        for eq in &self.equations {
+            eq.print();
+        }
+    }
+
+    pub fn solve_naive_gauss_el(&self) -> Result<Vec<f64>, SystemError> {
+        self.solve_gauss_el(Pivoting::None)
+    }
+
+    pub fn solve_gauss_el(&self, pivoting: Pivoting) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        let mut eqs = self.equations.clone();
+        let tolerance = self.singularity_tolerance();
+        // Row scale factors used by scaled partial pivoting
+        let scales = eqs
+            .iter()
+            .map(|eq| eq.coefficients.iter().fold(0.0_f64, |m, a| m.max(a.abs())))
+            .collect::<Vec<f64>>();
+        let mut order = (0..n).collect::<Vec<usize>>();
+
+        for k in 0..n {
+            let p = match pivoting {
+                Pivoting::None => k,
+                Pivoting::Partial => pivot_row(&eqs, k, |i| eqs[i].coefficients[k].abs()),
+                Pivoting::Scaled => {
+                    pivot_row(&eqs, k, |i| eqs[i].coefficients[k].abs() / scales[order[i]])
+                }
+            };
+            eqs.swap(k, p);
+            order.swap(k, p);
+
+            let pivot = eqs[k].coefficients[k];
+            if pivot.abs() <= tolerance {
+                return Err(match pivoting {
+                    Pivoting::None => SystemError::ZeroPivot { column: k, pivot },
+                    _ => SystemError::Singular { column: k, pivot },
+                });
+            }
+
+            // Divide the k-th equation by its k-th coefficient to get 1 on the diagonal
+            eqs[k] = eqs[k].divide(pivot);
+
+            // Subtract the k-th equation multiplied by the k-th coefficient from every
+            // equation below it to get 0 in the k-th column
+            for i in (k + 1)..n {
+                let factor = eqs[i].coefficients[k];
+                eqs[i] = eqs[i].subtract(&eqs[k].multiply(factor));
+            }
+        }
+
+        Ok(back_substitution(&eqs))
+    }
+
+    // Pivots at or below this magnitude are treated as zero
+    pub fn singularity_tolerance(&self) -> f64 {
+        let max_abs = self
+            .equations
+            .iter()
+            .flat_map(|eq| eq.coefficients.iter())
+            .fold(0.0_f64, |m, a| m.max(a.abs()));
+        self.size() as f64 * f64::EPSILON * max_abs
+    }
+}
+
+// Index of the row at or below k with the largest pivot score
+fn pivot_row(eqs: &[Equation], k: usize, score: impl Fn(usize) -> f64) -> usize {
+    (k..eqs.len()).fold(k, |best, i| if score(i) > score(best) { i } else { best })
+}
+
+// Solves an upper triangular system, starting from the last equation
+pub fn back_substitution(eqs: &[Equation]) -> Vec<f64> {
+    let n = eqs.len();
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = ((i + 1)..n).map(|j| eqs[i].coefficients[j] * x[j]).sum();
+        x[i] = (eqs[i].r - sum) / eqs[i].coefficients[i];
+    }
+    x
+}
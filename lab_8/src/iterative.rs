@@ -0,0 +1,169 @@
+use crate::equation::{EquationSystem, SystemError};
+use crate::residual::norm_inf;
+
+#[derive(Debug, Clone)]
+pub struct IterativeSolution {
+    pub x: Vec<f64>,
+    pub iterations: usize,
+    // Infinity norm of r - Ax after every iteration
+    pub residuals: Vec<f64>,
+    pub converged: bool,
+    pub diagonally_dominant: bool,
+}
+
+impl EquationSystem {
+    // Strict row diagonal dominance, a sufficient condition for Jacobi and Gauss-Seidel
+    pub fn is_diagonally_dominant(&self) -> bool {
+        self.equations.iter().enumerate().all(|(i, eq)| {
+            let off_diagonal: f64 = eq
+                .coefficients
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, a)| a.abs())
+                .sum();
+            eq.coefficients[i].abs() > off_diagonal
+        })
+    }
+
+    pub fn solve_jacobi(
+        &self,
+        tol: f64,
+        max_iter: usize,
+    ) -> Result<IterativeSolution, SystemError> {
+        self.iterate(tol, max_iter, |x| {
+            let previous = x.to_vec();
+            for (i, eq) in self.equations.iter().enumerate() {
+                let sum: f64 = eq
+                    .coefficients
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(j, a)| a * previous[j])
+                    .sum();
+                x[i] = (eq.r - sum) / eq.coefficients[i];
+            }
+        })
+    }
+
+    pub fn solve_gauss_seidel(
+        &self,
+        tol: f64,
+        max_iter: usize,
+    ) -> Result<IterativeSolution, SystemError> {
+        self.solve_sor(1.0, tol, max_iter)
+    }
+
+    pub fn solve_sor(
+        &self,
+        omega: f64,
+        tol: f64,
+        max_iter: usize,
+    ) -> Result<IterativeSolution, SystemError> {
+        if !(omega > 0.0 && omega < 2.0) {
+            return Err(SystemError::InvalidRelaxation(omega));
+        }
+        self.iterate(tol, max_iter, |x| {
+            for (i, eq) in self.equations.iter().enumerate() {
+                let sum: f64 = eq
+                    .coefficients
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(j, a)| a * x[j])
+                    .sum();
+                let gauss_seidel = (eq.r - sum) / eq.coefficients[i];
+                x[i] = (1.0 - omega) * x[i] + omega * gauss_seidel;
+            }
+        })
+    }
+
+    // Runs `step` from x = 0 until the residual drops below tol * ||r||
+    fn iterate(
+        &self,
+        tol: f64,
+        max_iter: usize,
+        mut step: impl FnMut(&mut [f64]),
+    ) -> Result<IterativeSolution, SystemError> {
+        let n = self.size();
+        for (i, eq) in self.equations.iter().enumerate() {
+            if eq.coefficients[i] == 0.0 {
+                return Err(SystemError::ZeroPivot {
+                    column: i,
+                    pivot: 0.0,
+                });
+            }
+        }
+
+        let diagonally_dominant = self.is_diagonally_dominant();
+
+        let r_norm = norm_inf(&self.rhs());
+        let threshold = tol * r_norm.max(f64::MIN_POSITIVE);
+        let mut x = vec![0.0; n];
+        let mut residuals = Vec::new();
+        let mut converged = false;
+
+        for _ in 0..max_iter {
+            step(&mut x);
+            let residual = norm_inf(&self.residual(&x)?);
+            residuals.push(residual);
+            if !residual.is_finite() {
+                return Err(SystemError::Diverged {
+                    iterations: residuals.len(),
+                });
+            }
+            if residual <= threshold {
+                converged = true;
+                break;
+            }
+        }
+
+        Ok(IterativeSolution {
+            x,
+            iterations: residuals.len(),
+            residuals,
+            converged,
+            diagonally_dominant,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::Pivoting;
+
+    #[test]
+    fn iterative_solutions_match_direct() {
+        let system = EquationSystem::from_augmented(vec![
+            vec![10.0, 2.0, -1.0, 27.0],
+            vec![-3.0, -6.0, 2.0, -61.5],
+            vec![1.0, 1.0, 5.0, -21.5],
+        ])
+        .unwrap();
+        let direct = system.solve_gauss_el(Pivoting::Partial).unwrap();
+        for result in [
+            system.solve_jacobi(1e-12, 200).unwrap(),
+            system.solve_gauss_seidel(1e-12, 200).unwrap(),
+            system.solve_sor(1.1, 1e-12, 200).unwrap(),
+        ] {
+            assert!(result.converged);
+            assert!(result.diagonally_dominant);
+            for (xi, di) in result.x.iter().zip(&direct) {
+                assert!((xi - di).abs() <= 1e-10, "{} differs from {}", xi, di);
+            }
+        }
+    }
+
+    #[test]
+    fn non_dominant_system_diverges() {
+        // Jacobi iteration matrix with spectral radius sqrt(6)
+        let system =
+            EquationSystem::from_augmented(vec![vec![1.0, 3.0, 4.0], vec![2.0, 1.0, 3.0]]).unwrap();
+        assert!(!system.is_diagonally_dominant());
+        assert!(matches!(
+            system.solve_jacobi(1e-12, 2000),
+            Err(SystemError::Diverged { .. })
+        ));
+    }
+}
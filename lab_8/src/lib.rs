@@ -0,0 +1,7 @@
+pub mod banded;
+pub mod cholesky;
+pub mod condition;
+pub mod equation;
+pub mod iterative;
+pub mod lu;
+pub mod residual;
@@ -0,0 +1,196 @@
+use crate::equation::{EquationSystem, SystemError};
+
+// Doolittle LU factorization with partial pivoting, PA = LU.
+// L has an implicit unit diagonal and is stored below the diagonal of `lu`,
+// U is stored on and above it.
+#[derive(Debug, Clone)]
+pub struct LuDecomposition {
+    lu: Vec<Vec<f64>>,
+    permutation: Vec<usize>,
+    swaps: usize,
+}
+
+impl LuDecomposition {
+    pub fn new(system: &EquationSystem) -> Result<LuDecomposition, SystemError> {
+        let n = system.size();
+        let tolerance = system.singularity_tolerance();
+        let mut lu = system.matrix();
+        let mut permutation = (0..n).collect::<Vec<usize>>();
+        let mut swaps = 0;
+
+        for k in 0..n {
+            let p = (k..n).fold(k, |best, i| {
+                if lu[i][k].abs() > lu[best][k].abs() {
+                    i
+                } else {
+                    best
+                }
+            });
+            if p != k {
+                lu.swap(k, p);
+                permutation.swap(k, p);
+                swaps += 1;
+            }
+
+            let pivot = lu[k][k];
+            if pivot.abs() <= tolerance {
+                return Err(SystemError::Singular { column: k, pivot });
+            }
+
+            let pivot_row = lu[k].clone();
+            for row in lu.iter_mut().skip(k + 1) {
+                let factor = row[k] / pivot;
+                row[k] = factor;
+                for (a, p) in row.iter_mut().zip(&pivot_row).skip(k + 1) {
+                    *a -= factor * p;
+                }
+            }
+        }
+
+        Ok(LuDecomposition {
+            lu,
+            permutation,
+            swaps,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.lu.len()
+    }
+
+    pub fn l(&self) -> Vec<Vec<f64>> {
+        let n = self.size();
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match i.cmp(&j) {
+                        std::cmp::Ordering::Greater => self.lu[i][j],
+                        std::cmp::Ordering::Equal => 1.0,
+                        std::cmp::Ordering::Less => 0.0,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn u(&self) -> Vec<Vec<f64>> {
+        let n = self.size();
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| if j >= i { self.lu[i][j] } else { 0.0 })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Row i of PA is row permutation[i] of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn solve(&self, r: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+
+        // Forward substitution, Ly = Pr
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|j| self.lu[i][j] * y[j]).sum();
+            y[i] = r[self.permutation[i]] - sum;
+        }
+
+        // Back substitution, Ux = y
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n).map(|j| self.lu[i][j] * x[j]).sum();
+            x[i] = (y[i] - sum) / self.lu[i][i];
+        }
+        Ok(x)
+    }
+
+    // Solves A^T x = r by reusing the factorization, U^T L^T P x = r
+    pub fn solve_transpose(&self, r: &[f64]) -> Result<Vec<f64>, SystemError> {
+        let n = self.size();
+        if r.len() != n {
+            return Err(SystemError::DimensionMismatch {
+                expected: n,
+                found: r.len(),
+            });
+        }
+
+        // Forward substitution, U^T z = r
+        let mut z = vec![0.0; n];
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|j| self.lu[j][i] * z[j]).sum();
+            z[i] = (r[i] - sum) / self.lu[i][i];
+        }
+
+        // Back substitution, L^T y = z
+        let mut y = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n).map(|j| self.lu[j][i] * y[j]).sum();
+            y[i] = z[i] - sum;
+        }
+
+        // Undo the row permutation, x = P^T y
+        let mut x = vec![0.0; n];
+        for (i, p) in self.permutation.iter().enumerate() {
+            x[*p] = y[i];
+        }
+        Ok(x)
+    }
+
+    pub fn solve_many(&self, rhs: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, SystemError> {
+        rhs.iter().map(|r| self.solve(r)).collect()
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let sign = if self.swaps.is_multiple_of(2) {
+            1.0
+        } else {
+            -1.0
+        };
+        (0..self.size()).fold(sign, |det, i| det * self.lu[i][i])
+    }
+
+    pub fn inverse(&self) -> Vec<Vec<f64>> {
+        let n = self.size();
+        let mut inverse = vec![vec![0.0; n]; n];
+        for j in 0..n {
+            let mut e = vec![0.0; n];
+            e[j] = 1.0;
+            let column = self.solve(&e).expect("unit vector has the right length");
+            for i in 0..n {
+                inverse[i][j] = column[i];
+            }
+        }
+        inverse
+    }
+}
+
+impl EquationSystem {
+    pub fn lu_decompose(&self) -> Result<LuDecomposition, SystemError> {
+        LuDecomposition::new(self)
+    }
+
+    pub fn solve_lu(&self) -> Result<Vec<f64>, SystemError> {
+        self.lu_decompose()?.solve(&self.rhs())
+    }
+
+    // Like solve_lu, but refuses systems with no correct digits left, cond(A) eps >= 1.
+    // The condition number is estimated from the same factors, which adds O(n^2) work.
+    pub fn solve_lu_checked(&self) -> Result<Vec<f64>, SystemError> {
+        let lu = self.lu_decompose()?;
+        let condition_number = self.matrix_norm_1() * lu.inverse_norm_estimate()?;
+        if condition_number * f64::EPSILON >= 1.0 {
+            return Err(SystemError::IllConditioned { condition_number });
+        }
+        lu.solve(&self.rhs())
+    }
+}
@@ -1,120 +1,210 @@
-struct Equation {
-    x1: f64,
-    x2: f64,
-    x3: f64,
-    r: f64,
+use lab_8::banded::{BandedSystem, TridiagonalSystem};
+use lab_8::condition::matrix_norm_inf;
+use lab_8::equation::{Equation, EquationSystem, Pivoting, SystemError};
+
+// Hilbert matrix with the right-hand side chosen so that the exact solution is all ones
+fn hilbert(n: usize) -> Result<EquationSystem, SystemError> {
+    let a = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| 1.0 / (i + j + 1) as f64)
+                .collect::<Vec<f64>>()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    let r = a.iter().map(|row| row.iter().sum()).collect();
+    EquationSystem::from_matrix(a, r)
 }
 
-impl Equation {
-    fn new(x1: f64, x2: f64, x3: f64, r: f64) -> Equation {
-        Equation { x1, x2, x3, r }
+fn hilbert_error_bounds() -> Result<(), SystemError> {
+    println!("Hilbert matrices, naive Gauss elimination:");
+    for n in [2, 4, 6, 8, 10, 12] {
+        let system = hilbert(n)?;
+        let x = system.solve_naive_gauss_el()?;
+        let bound = system.forward_error_bound(&x)?;
+        let error = x.iter().map(|xi| (xi - 1.0).abs()).sum::<f64>() / n as f64;
+        println!(
+            "n = {:2}, cond = {:e} (estimate {:e}), actual error = {:e}, bound = {:e}, within bound = {}",
+            n,
+            bound.condition_number,
+            system.condition_number_estimate()?,
+            error,
+            bound.bound,
+            error <= bound.bound
+        );
     }
-
-    fn multiply(&self, value: f64) -> Equation {
-        Equation {
-            x1: self.x1 * value,
-            x2: self.x2 * value,
-            x3: self.x3 * value,
-            r: self.r * value,
-        }
-    }
-
-    fn divide(&self, value: f64) -> Equation {
-        Equation {
-            x1: self.x1 / value,
-            x2: self.x2 / value,
-            x3: self.x3 / value,
-            r: self.r / value,
-        }
-    }
-
-    fn add(&mut self, other: &Equation) -> Equation {
-        Equation {
-            x1: self.x1 + other.x1,
-            x2: self.x2 + other.x2,
-            x3: self.x3 + other.x3,
-            r: self.r + other.r,
-        }
+    if let Err(e) = hilbert(12)?.solve_lu_checked() {
+        println!("Checked LU solve, n = 12: {}", e);
     }
+    Ok(())
+}
 
-    fn subtract(&mut self, other: &Equation) -> Equation {
-        Equation {
-            x1: self.x1 - other.x1,
-            x2: self.x2 - other.x2,
-            x3: self.x3 - other.x3,
-            r: self.r - other.r,
-        }
-    }
+fn tridiagonal_and_banded() -> Result<(), SystemError> {
+    // Implicit finite differences for -u'' = 1 on (0, 1) with u(0) = u(1) = 0
+    let n = 9;
+    let h: f64 = 1.0 / (n + 1) as f64;
+    let tridiagonal = TridiagonalSystem::new(
+        vec![-1.0; n - 1],
+        vec![2.0; n],
+        vec![-1.0; n - 1],
+        vec![h.powi(2); n],
+    )?;
+    let u = tridiagonal.solve()?;
+    println!("Thomas algorithm: {:?}", u);
+    tridiagonal.check_solution(&u, 1e-12)?.print();
+
+    // Pentadiagonal system solved in band storage and with dense elimination
+    let n: usize = 8;
+    let a = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| match i.abs_diff(j) {
+                    0 => 6.0,
+                    1 => -4.0,
+                    2 => 1.0,
+                    _ => 0.0,
+                })
+                .collect::<Vec<f64>>()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    let dense = EquationSystem::from_matrix(a, vec![1.0; n])?;
+    let banded = BandedSystem::from_equation_system(&dense, 2, 2);
+    let x = banded.solve()?;
+    println!("Banded solver: {:?}", x);
+    println!(
+        "Dense solver:  {:?}",
+        dense.solve_gauss_el(Pivoting::Partial)?
+    );
+    banded.check_solution(&x, 1e-12)?.print();
+    Ok(())
+}
 
-    fn print(&self) {
-        println!("{}x1 + {}x2 + {}x3 = {}", self.x1, self.x2, self.x3, self.r);
+fn symmetric_systems() -> Result<(), SystemError> {
+    let spd = EquationSystem::from_augmented(vec![
+        vec![4.0, 12.0, -16.0, 1.0],
+        vec![12.0, 37.0, -43.0, 2.0],
+        vec![-16.0, -43.0, 98.0, 3.0],
+    ])?;
+    let cholesky = spd.cholesky()?;
+    println!("Cholesky L = {:?}", cholesky.l());
+    println!("Cholesky solution: {:?}", spd.solve_cholesky()?);
+    println!(
+        "Gauss solution:    {:?}",
+        spd.solve_gauss_el(Pivoting::Partial)?
+    );
+
+    let indefinite = EquationSystem::from_augmented(vec![
+        vec![1.0, 2.0, 3.0, 6.0],
+        vec![2.0, -4.0, 1.0, -1.0],
+        vec![3.0, 1.0, 2.0, 6.0],
+    ])?;
+    if let Err(e) = indefinite.cholesky() {
+        println!("Cholesky: {}", e);
     }
+    let ldlt = indefinite.ldlt()?;
+    println!(
+        "LDL^T D = {:?}, subdiagonal = {:?}, inertia = {:?}",
+        ldlt.d(),
+        ldlt.e(),
+        ldlt.inertia()
+    );
+    println!("LDL^T solution: {:?}", ldlt.solve(&indefinite.rhs())?);
+    Ok(())
 }
 
-struct EquationSystem {
-    eq1: Equation,
-    eq2: Equation,
-    eq3: Equation,
-}
+fn main() -> Result<(), SystemError> {
+    let eq1 = Equation::new(vec![10.0, 2.0, -1.0], 27.0);
+    let eq2 = Equation::new(vec![-3.0, -6.0, 2.0], -61.5);
+    let eq3 = Equation::new(vec![1.0, 1.0, 5.0], -21.5);
 
-impl EquationSystem {
-    fn new(eq1: Equation, eq2: Equation, eq3: Equation) -> EquationSystem {
-        EquationSystem { eq1, eq2, eq3 }
+    let eq_sys = EquationSystem::new(vec![eq1, eq2, eq3])?;
+    eq_sys.print();
+    eq_sys.check_solution(&[0.5, 8.0, -6.0], 1e-12)?.print();
+
+    let x = eq_sys.solve_naive_gauss_el()?;
+    let solution = x
+        .iter()
+        .enumerate()
+        .map(|(i, xi)| format!("x{} = {}", i + 1, xi))
+        .collect::<Vec<String>>();
+    println!("{}", solution.join(", "));
+    eq_sys.check_solution(&x, 1e-12)?.print();
+
+    // Zero in the first pivot position: the naive method fails, pivoting does not
+    let zero_pivot = EquationSystem::from_augmented(vec![
+        vec![0.0, 2.0, 1.0, 7.0],
+        vec![1.0, 1.0, 1.0, 6.0],
+        vec![2.0, 1.0, -1.0, 1.0],
+    ])?;
+    match zero_pivot.solve_naive_gauss_el() {
+        Ok(x) => println!("Naive: {:?}", x),
+        Err(e) => println!("Naive: {}", e),
     }
-
-    fn print(&self) {
-        self.eq1.print();
-        self.eq2.print();
-        self.eq3.print();
+    println!(
+        "Partial pivoting: {:?}",
+        zero_pivot.solve_gauss_el(Pivoting::Partial)?
+    );
+    println!(
+        "Scaled pivoting: {:?}",
+        zero_pivot.solve_gauss_el(Pivoting::Scaled)?
+    );
+
+    let singular = EquationSystem::from_augmented(vec![vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]])?;
+    if let Err(e) = singular.solve_gauss_el(Pivoting::Partial) {
+        println!("Singular system: {}", e);
     }
 
-    fn solve_naive_gauss_el(&mut self) -> (f64, f64, f64) {
-        // Divide the first equation by x1 to get 1 as the coefficient of x1
-        let eq1 = self.eq1.divide(self.eq1.x1);
-
-        // Subtract the first equation multiplied by x1 from the second and third equations
-        // to get 0 as the coefficient of x1
-        let mut eq2 = self.eq2.subtract(&eq1.multiply(self.eq2.x1));
-        let mut eq3 = self.eq3.subtract(&eq1.multiply(self.eq3.x1));
-
-        // Divide the second equation by x2 to get 1 as the coefficient of x2
-        eq2 = eq2.divide(eq2.x2);
-
-        // Subtract the second equation multiplied by x2 coefficient from the third equation
-        // to get 0 as the coefficient of x2
-        eq3 = eq3.subtract(&eq2.multiply(eq3.x2));
-
-        let x3 = eq3.r / eq3.x3;
-        let x2 = (eq2.r - eq2.x3 * x3) / eq2.x2;
-        let x1 = (eq1.r - eq1.x2 * x2 - eq1.x3 * x3) / eq1.x1;
-
-        return (x1, x2, x3);
+    // Factorize once, then reuse the factorization for several right-hand sides
+    let lu = eq_sys.lu_decompose()?;
+    println!("LU solution: {:?}", eq_sys.solve_lu()?);
+    let loads = vec![
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![27.0, -61.5, -21.5],
+    ];
+    for (r, x) in loads.iter().zip(lu.solve_many(&loads)?) {
+        println!("r = {:?} -> x = {:?}", r, x);
     }
-
-    fn check_solution(&self, x1: f64, x2: f64, x3: f64) {
-        let eq1 = self.eq1.x1 * x1 + self.eq1.x2 * x2 + self.eq1.x3 * x3;
-        let eq2 = self.eq2.x1 * x1 + self.eq2.x2 * x2 + self.eq2.x3 * x3;
-        let eq3 = self.eq3.x1 * x1 + self.eq3.x2 * x2 + self.eq3.x3 * x3;
-
-        let is_correct = eq1 == self.eq1.r && eq2 == self.eq2.r && eq3 == self.eq3.r;
-
-        if is_correct {
-            println!("The solution is correct.");
-        } else {
-            println!("The solution is incorrect.");
+    println!("det(A) = {}", lu.determinant());
+    println!("inv(A) = {:?}", lu.inverse());
+
+    println!(
+        "||A||_inf = {}, cond(A) = {}",
+        matrix_norm_inf(&eq_sys.matrix()),
+        eq_sys.condition_number()?
+    );
+    hilbert_error_bounds()?;
+
+    println!("Diagonally dominant: {}", eq_sys.is_diagonally_dominant());
+    let jacobi = eq_sys.solve_jacobi(1e-10, 100)?;
+    let gauss_seidel = eq_sys.solve_gauss_seidel(1e-10, 100)?;
+    let sor = eq_sys.solve_sor(1.1, 1e-10, 100)?;
+    for (name, result) in [
+        ("Jacobi", jacobi),
+        ("Gauss-Seidel", gauss_seidel),
+        ("SOR", sor),
+    ] {
+        if !result.diagonally_dominant {
+            println!(
+                "Warning: the matrix is not diagonally dominant, {} may diverge",
+                name
+            );
         }
+        println!(
+            "{}: x = {:?}, iterations = {}, converged = {}, final residual = {:e}",
+            name,
+            result.x,
+            result.iterations,
+            result.converged,
+            result.residuals.last().unwrap_or(&f64::NAN)
+        );
     }
-}
 
-fn main() {
-    let eq1 = Equation::new(10.0, 2.0, -1.0, 27.0);
-    let eq2 = Equation::new(-3.0, -6.0, 2.0, -61.5);
-    let eq3 = Equation::new(1.0, 1.0, 5.0, -21.5);
+    tridiagonal_and_banded()?;
+    symmetric_systems()?;
 
-    let mut eq_sys = EquationSystem::new(eq1, eq2, eq3);
-    eq_sys.print();
-    eq_sys.check_solution(0.5, 8.0, -6.0);
-
-    let (x1, x2, x3) = eq_sys.solve_naive_gauss_el();
-    print!("x1 = {}, x2 = {}, x3 = {}", x1, x2, x3);
+    let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0, 5.0]];
+    if let Err(e) = EquationSystem::from_augmented(rows) {
+        println!("Invalid system: {}", e);
+    }
+    Ok(())
 }
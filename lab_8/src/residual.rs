@@ -0,0 +1,152 @@
+use crate::condition::matrix_norm_inf;
+use crate::equation::{EquationSystem, SystemError};
+
+pub fn norm_1(v: &[f64]) -> f64 {
+    v.iter().map(|a| a.abs()).sum()
+}
+
+pub fn norm_2(v: &[f64]) -> f64 {
+    v.iter().map(|a| a * a).sum::<f64>().sqrt()
+}
+
+// f64::max drops NaN, so a NaN component is passed on explicitly
+pub fn norm_inf(v: &[f64]) -> f64 {
+    v.iter().fold(0.0, |m: f64, a| {
+        if m.is_nan() || a.is_nan() {
+            f64::NAN
+        } else {
+            m.max(a.abs())
+        }
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct ResidualCheck {
+    // r - Ax for every equation
+    pub residual: Vec<f64>,
+    pub norm_1: f64,
+    pub norm_2: f64,
+    pub norm_inf: f64,
+    // ||r - Ax|| / (||A|| ||x|| + ||r||) in the infinity norm
+    pub relative_residual: f64,
+    pub tolerance: f64,
+    pub passed: bool,
+}
+
+impl ResidualCheck {
+    // `matrix_norm` is ||A|| in the infinity norm
+    pub fn new(
+        residual: Vec<f64>,
+        x: &[f64],
+        rhs: &[f64],
+        matrix_norm: f64,
+        tolerance: f64,
+    ) -> ResidualCheck {
+        let residual_inf = norm_inf(&residual);
+        let scale = matrix_norm * norm_inf(x) + norm_inf(rhs);
+        let relative_residual = if scale > 0.0 {
+            residual_inf / scale
+        } else {
+            residual_inf
+        };
+
+        ResidualCheck {
+            norm_1: norm_1(&residual),
+            norm_2: norm_2(&residual),
+            norm_inf: residual_inf,
+            relative_residual,
+            tolerance,
+            passed: relative_residual <= tolerance,
+            residual,
+        }
+    }
+
+    pub fn print(&self) {
+        if self.passed {
+            println!("The solution is correct.");
+        } else {
+            println!("The solution is incorrect.");
+        }
+        println!(
+            "Residual norms: 1 = {:e}, 2 = {:e}, inf = {:e}, relative = {:e} (tolerance {:e})",
+            self.norm_1, self.norm_2, self.norm_inf, self.relative_residual, self.tolerance
+        );
+    }
+}
+
+impl EquationSystem {
+    pub fn residual(&self, x: &[f64]) -> Result<Vec<f64>, SystemError> {
+        if x.len() != self.size() {
+            return Err(SystemError::DimensionMismatch {
+                expected: self.size(),
+                found: x.len(),
+            });
+        }
+        Ok(self
+            .equations
+            .iter()
+            .map(|eq| eq.r - eq.evaluate(x))
+            .collect())
+    }
+
+    // The solution passes when the relative residual is at most `tolerance`
+    pub fn check_solution(&self, x: &[f64], tolerance: f64) -> Result<ResidualCheck, SystemError> {
+        let residual = self.residual(x)?;
+        Ok(ResidualCheck::new(
+            residual,
+            x,
+            &self.rhs(),
+            matrix_norm_inf(&self.matrix()),
+            tolerance,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system() -> EquationSystem {
+        EquationSystem::from_augmented(vec![
+            vec![10.0, 2.0, -1.0, 27.0],
+            vec![-3.0, -6.0, 2.0, -61.5],
+            vec![1.0, 1.0, 5.0, -21.5],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn norms_propagate_nan() {
+        let v = [1.0, f64::NAN, -3.0];
+        assert!(norm_1(&v).is_nan());
+        assert!(norm_2(&v).is_nan());
+        assert!(norm_inf(&v).is_nan());
+        assert!(norm_inf(&[f64::NAN, 1.0]).is_nan());
+        assert_eq!(norm_inf(&[1.0, -3.0, 2.0]), 3.0);
+    }
+
+    #[test]
+    fn correct_solution_passes() {
+        let check = system().check_solution(&[0.5, 8.0, -6.0], 1e-12).unwrap();
+        assert!(check.passed);
+        assert!(check.relative_residual <= 1e-15);
+    }
+
+    #[test]
+    fn perturbed_solution_fails() {
+        let check = system()
+            .check_solution(&[0.5, 8.0 + 1e-6, -6.0], 1e-12)
+            .unwrap();
+        assert!(!check.passed);
+        assert!(check.norm_inf > 1e-6);
+    }
+
+    #[test]
+    fn nan_solution_fails() {
+        let check = system()
+            .check_solution(&[0.5, f64::NAN, -6.0], 1e-12)
+            .unwrap();
+        assert!(!check.passed);
+        assert!(check.relative_residual.is_nan());
+    }
+}